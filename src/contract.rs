@@ -1,18 +1,45 @@
-use self::execute::{buy_shares, sell_shares};
+use self::execute::{
+    accept_admin, batch, buy_shares, buy_shares_with_funds, cancel_admin_proposal, claim_fees,
+    claim_referral_fees, decrease_allowance, increase_allowance, migrate_indexes, pause,
+    propose_new_admin, sell_all, sell_shares, set_transfers_enabled, transfer_shares,
+    transfer_shares_from, unpause, update_config, update_protocol_fee_recipients,
+    withdraw_protocol_fees,
+};
+use crate::curve::{BondingCurve, CurveConfig};
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Config, CONFIG};
+use crate::migrations::{migrate_state, v0_2_0::INDEX_BACKFILL};
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, MigrateMsg, ProtocolFeeRecipient, QueryMsg, TradeSimulation,
+};
+use crate::state::{
+    load_subject_config, load_supply, Config, FeeRecipient, SubjectBounds, ADMIN, CONFIG,
+    SHARES_BALANCE,
+};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+    ensure, to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    Uint128,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use cw_utils::nonpayable;
+use sg_std::NATIVE_DENOM;
 
 const CONTRACT_NAME: &str = "crates.io:fren-party";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Upper bound for each of the protocol and subject fees (10%).
+pub const MAX_FEE_BPS: u64 = 1_000;
+
+/// 100% in basis points.
+const MAX_BPS: u64 = 10_000;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+const DEFAULT_MIGRATION_LIMIT: u32 = 100;
+const MAX_MIGRATION_LIMIT: u32 = 500;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -24,172 +51,1434 @@ pub fn instantiate(
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    let denom = msg.denom.unwrap_or_else(|| NATIVE_DENOM.to_string());
+    ensure!(!denom.trim().is_empty(), ContractError::InvalidDenom {});
+
     let config = Config {
-        protocol_fee_destination: deps.api.addr_validate(&msg.protocol_fee_destination)?,
-        protocol_fee_percent: Decimal::bps(msg.protocol_fee_bps),
-        subject_fee_percent: Decimal::bps(msg.subject_fee_bps),
-        curve_coefficient: msg.curve_coefficient,
+        denom,
+        protocol_fee_recipients: validate_fee_recipients(
+            deps.as_ref(),
+            msg.protocol_fee_recipients,
+        )?,
+        protocol_fee_percent: fee_percent(msg.protocol_fee_bps)?,
+        subject_fee_percent: fee_percent(msg.subject_fee_bps)?,
+        referral_fee_percent: share_percent(msg.referral_fee_bps)?,
+        burn_percent: share_percent(msg.burn_bps)?,
+        curve: validate_curve(msg.curve)?,
+        subject_bounds: msg.subject_bounds.map(validate_bounds).transpose()?,
     };
     CONFIG.save(deps.storage, &config)?;
+    ADMIN.save(deps.storage, &info.sender)?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
         .add_attribute("owner", info.sender))
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    ensure!(
+        stored.contract == CONTRACT_NAME,
+        ContractError::WrongContract {
+            expected: CONTRACT_NAME.to_string(),
+            actual: stored.contract,
+        }
+    );
+
+    let stored_version: semver::Version = stored.version.parse()?;
+    let new_version: semver::Version = CONTRACT_VERSION.parse()?;
+    ensure!(
+        stored_version <= new_version,
+        ContractError::CannotDowngrade {
+            stored: stored.version,
+            new: CONTRACT_VERSION.to_string(),
+        }
+    );
+
+    migrate_state(deps.branch(), &stored_version, &msg)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::BuyShares { subject, amount } => buy_shares(deps, info, subject, amount),
-        ExecuteMsg::SellShares { subject, amount } => {
-            sell_shares(deps, info, subject, amount.into())
+        ExecuteMsg::BuyShares {
+            subject,
+            amount,
+            recipient,
+            referrer,
+            max_total_cost,
+            deadline,
+            settings,
+        } => buy_shares(
+            deps,
+            env,
+            info,
+            subject,
+            amount,
+            recipient,
+            referrer,
+            max_total_cost,
+            deadline,
+            settings,
+        ),
+        ExecuteMsg::BuySharesWithFunds {
+            subject,
+            min_amount,
+        } => buy_shares_with_funds(deps, env, info, subject, min_amount),
+        ExecuteMsg::SellShares {
+            subject,
+            amount,
+            recipient,
+            referrer,
+            min_proceeds,
+            deadline,
+            msg,
+        } => sell_shares(
+            deps,
+            env,
+            info,
+            subject,
+            amount.into(),
+            recipient,
+            referrer,
+            min_proceeds,
+            deadline,
+            msg,
+        ),
+        ExecuteMsg::SellAll {
+            subject,
+            min_proceeds,
+        } => sell_all(deps, env, info, subject, min_proceeds),
+        ExecuteMsg::Batch { trades } => batch(deps, env, info, trades),
+        ExecuteMsg::TransferShares {
+            subject,
+            recipient,
+            amount,
+        } => transfer_shares(deps, info, subject, recipient, amount),
+        ExecuteMsg::IncreaseAllowance {
+            subject,
+            spender,
+            amount,
+            expires,
+        } => increase_allowance(deps, env, info, subject, spender, amount, expires),
+        ExecuteMsg::DecreaseAllowance {
+            subject,
+            spender,
+            amount,
+            expires,
+        } => decrease_allowance(deps, env, info, subject, spender, amount, expires),
+        ExecuteMsg::TransferSharesFrom {
+            subject,
+            owner,
+            recipient,
+            amount,
+        } => transfer_shares_from(deps, env, info, subject, owner, recipient, amount),
+        ExecuteMsg::SetTransfersEnabled { subject, enabled } => {
+            set_transfers_enabled(deps, info, subject, enabled)
+        }
+        ExecuteMsg::UpdateConfig {
+            protocol_fee_bps,
+            subject_fee_bps,
+            referral_fee_bps,
+            burn_bps,
+            curve,
+            subject_bounds,
+        } => update_config(
+            deps,
+            info,
+            protocol_fee_bps,
+            subject_fee_bps,
+            referral_fee_bps,
+            burn_bps,
+            curve,
+            subject_bounds,
+        ),
+        ExecuteMsg::UpdateProtocolFeeRecipients { recipients } => {
+            update_protocol_fee_recipients(deps, info, recipients)
+        }
+        ExecuteMsg::MigrateIndexes { limit } => migrate_indexes(deps, info, limit),
+        ExecuteMsg::ProposeNewAdmin { new_admin, expiry } => {
+            propose_new_admin(deps, env, info, new_admin, expiry)
         }
+        ExecuteMsg::AcceptAdmin {} => accept_admin(deps, env, info),
+        ExecuteMsg::CancelAdminProposal {} => cancel_admin_proposal(deps, info),
+        ExecuteMsg::Pause {
+            subject,
+            allow_sells,
+        } => pause(deps, info, subject, allow_sells),
+        ExecuteMsg::Unpause { subject } => unpause(deps, info, subject),
+        ExecuteMsg::ClaimFees {} => claim_fees(deps, info),
+        ExecuteMsg::ClaimReferralFees {} => claim_referral_fees(deps, info),
+        ExecuteMsg::WithdrawProtocolFees {} => withdraw_protocol_fees(deps, info),
+    }
+}
+
+/// Converts a fee in basis points to a percentage, enforcing `MAX_FEE_BPS`.
+fn fee_percent(bps: u64) -> Result<Decimal, ContractError> {
+    ensure!(
+        bps <= MAX_FEE_BPS,
+        ContractError::FeeTooHigh {
+            bps,
+            max: MAX_FEE_BPS,
+        }
+    );
+    Ok(Decimal::bps(bps))
+}
+
+/// Converts a share of the protocol fee in basis points to a percentage.
+fn share_percent(bps: u64) -> Result<Decimal, ContractError> {
+    ensure!(
+        bps <= MAX_BPS,
+        ContractError::FeeTooHigh { bps, max: MAX_BPS }
+    );
+    Ok(Decimal::bps(bps))
+}
+
+fn validate_fee_recipients(
+    deps: Deps,
+    recipients: Vec<ProtocolFeeRecipient>,
+) -> Result<Vec<FeeRecipient>, ContractError> {
+    let invalid = |reason: &str| ContractError::InvalidFeeRecipients {
+        reason: reason.to_string(),
+    };
+
+    ensure!(!recipients.is_empty(), invalid("at least one is required"));
+
+    let mut total = 0u64;
+    let mut validated: Vec<FeeRecipient> = Vec::with_capacity(recipients.len());
+    for recipient in recipients {
+        let address = deps.api.addr_validate(&recipient.address)?;
+        ensure!(
+            recipient.weight_bps > 0,
+            invalid("weights must be positive")
+        );
+        ensure!(
+            !validated.iter().any(|other| other.address == address),
+            invalid("duplicate recipient")
+        );
+
+        total = total.saturating_add(recipient.weight_bps);
+        validated.push(FeeRecipient {
+            address,
+            weight: Decimal::bps(recipient.weight_bps),
+        });
     }
+    ensure!(
+        total == MAX_BPS,
+        invalid("weights must add up to 10000 bps")
+    );
+
+    Ok(validated)
+}
+
+fn validate_curve(curve: CurveConfig) -> Result<CurveConfig, ContractError> {
+    curve.validate()?;
+    Ok(curve)
+}
+
+fn validate_bounds(bounds: SubjectBounds) -> Result<SubjectBounds, ContractError> {
+    ensure!(
+        !bounds.min_curve_coefficient.is_zero(),
+        ContractError::InvalidCurveCoefficient {}
+    );
+    ensure!(
+        bounds.min_curve_coefficient <= bounds.max_curve_coefficient,
+        ContractError::SubjectSettingsOutOfBounds {}
+    );
+    fee_percent(bounds.max_subject_fee_bps)?;
+    Ok(bounds)
+}
+
+/// Quotes buying `amount` shares of `subject` for `trader`. `buy_shares` prices
+/// trades with this too, so `SimulateBuy` always matches execution.
+fn simulate_buy(
+    deps: Deps,
+    subject: &Addr,
+    trader: &Addr,
+    amount: Uint128,
+) -> Result<TradeSimulation, ContractError> {
+    let supply = load_supply(deps.storage, subject.clone())?;
+
+    ensure!(
+        supply > 0 || subject == trader,
+        ContractError::NotSubject {
+            subject: subject.to_string()
+        }
+    );
+
+    let config = load_subject_config(deps.storage, subject.clone())?;
+    let price = config.curve.price(supply, amount.u128())?;
+    let (protocol_fee, subject_fee) = config.fees(price);
+
+    let balance = SHARES_BALANCE
+        .may_load(deps.storage, (subject.clone(), trader.clone()))?
+        .unwrap_or_default();
+
+    Ok(TradeSimulation {
+        price,
+        protocol_fee,
+        subject_fee,
+        total: price.checked_add(protocol_fee)?.checked_add(subject_fee)?,
+        supply: Uint128::from(supply).checked_add(amount)?,
+        balance: balance.checked_add(amount)?,
+    })
+}
+
+/// Quotes selling `amount` shares of `subject` for `trader`. `sell_shares` prices
+/// trades with this too, so `SimulateSell` always matches execution.
+fn simulate_sell(
+    deps: Deps,
+    subject: &Addr,
+    trader: &Addr,
+    amount: Uint128,
+) -> Result<TradeSimulation, ContractError> {
+    let supply = load_supply(deps.storage, subject.clone())?;
+
+    ensure!(supply > amount.u128(), ContractError::LastShare {});
+
+    let balance = SHARES_BALANCE
+        .may_load(deps.storage, (subject.clone(), trader.clone()))?
+        .unwrap_or_default();
+
+    ensure!(balance >= amount, ContractError::NotEnoughShares {});
+
+    let config = load_subject_config(deps.storage, subject.clone())?;
+    let price = config.curve.price(supply - amount.u128(), amount.u128())?;
+    let (protocol_fee, subject_fee) = config.fees(price);
+
+    Ok(TradeSimulation {
+        price,
+        protocol_fee,
+        subject_fee,
+        total: price.checked_sub(protocol_fee)?.checked_sub(subject_fee)?,
+        supply: Uint128::from(supply) - amount,
+        balance: balance - amount,
+    })
+}
+
+/// The most shares of `subject` that `funds` can buy including fees. Works for
+/// every curve family by searching the total cost, which grows with the amount.
+fn max_buy_for_funds(deps: Deps, subject: &Addr, funds: Uint128) -> Result<Uint128, ContractError> {
+    let supply = load_supply(deps.storage, subject.clone())?;
+    let config = load_subject_config(deps.storage, subject.clone())?;
+
+    let affordable = |amount: u128| -> bool {
+        let total = config.curve.price(supply, amount).and_then(|price| {
+            let (protocol_fee, subject_fee) = config.fees(price);
+            Ok(price.checked_add(protocol_fee)?.checked_add(subject_fee)?)
+        });
+        // amounts too large to price are never affordable
+        matches!(total, Ok(total) if total <= funds)
+    };
+
+    // find an unaffordable upper bound, then bisect down to the largest affordable amount
+    let mut low = 0u128;
+    let mut high = 1u128;
+    while affordable(high) {
+        low = high;
+        high = high.checked_mul(2).ok_or(ContractError::Overflow {})?;
+    }
+    while high - low > 1 {
+        let middle = low + (high - low) / 2;
+        if affordable(middle) {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+
+    Ok(low.into())
 }
 
 pub mod execute {
     use super::*;
     use crate::{
-        msg::TradeEvent,
-        state::{decrement_shares, increment_shares, load_supply, Config, CONFIG, SHARES_BALANCE},
+        msg::{ReceiveMsg, SubjectSettings, Trade, TradeEvent, TransferEvent},
+        state::{
+            accrue_fees, accrue_referral_fee, decrement_shares, increment_shares,
+            load_subject_status, load_supply, load_trading_status, sellable_balance, Allowance,
+            PendingAdmin, SubjectConfig, SubjectInfo, TradingStatus, ADMIN, ALLOWANCES, CONFIG,
+            PENDING_ADMIN, PROTOCOL_FEES, REFERRAL_FEES, SUBJECTS, SUBJECT_CONFIG, SUBJECT_FEES,
+            SUBJECT_STATUS, TRADING_STATUS, TRANSFERS_DISABLED,
+        },
     };
-    use cosmwasm_std::{ensure, Uint128};
-    use cw_utils::must_pay;
-    use sg_std::{send_msg, NATIVE_DENOM};
+    use cosmwasm_std::{coins, BankMsg, Empty, Storage, WasmMsg};
+    use cw_utils::{may_pay, must_pay, Expiration};
 
+    /// Parts of a trade's protocol fee that do not accrue to the protocol.
+    struct FeeSplit {
+        referral: Uint128,
+        burn: Uint128,
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn buy_shares(
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
         subject: String,
         amount: Uint128,
+        recipient: Option<String>,
+        referrer: Option<String>,
+        max_total_cost: Option<Uint128>,
+        deadline: Option<Expiration>,
+        settings: Option<SubjectSettings>,
     ) -> Result<Response, ContractError> {
+        check_deadline(&env, deadline)?;
+
         let subject = deps.api.addr_validate(&subject)?;
-        let payment = must_pay(&info, NATIVE_DENOM)?.into();
+        let recipient = recipient
+            .map(|recipient| deps.api.addr_validate(&recipient))
+            .transpose()?
+            .unwrap_or_else(|| info.sender.clone());
+        let referrer = validate_referrer(deps.as_ref(), referrer, &info.sender, &recipient)?;
+        ensure_trading(deps.as_ref(), &subject, true)?;
+        let denom = CONFIG.load(deps.storage)?.denom;
+        let payment = must_pay(&info, &denom)?;
         let supply = load_supply(deps.storage, subject.clone())?;
 
+        // the first share can not be gifted, the subject has to buy it themselves
         ensure!(
-            supply > 0 || subject == info.sender,
+            supply > 0 || info.sender == subject,
             ContractError::NotSubject {
                 subject: subject.to_string()
             }
         );
 
-        let Config {
-            protocol_fee_destination,
-            protocol_fee_percent,
-            subject_fee_percent,
-            curve_coefficient,
-        } = CONFIG.load(deps.storage)?;
-
-        let price = price(supply, amount, curve_coefficient);
+        if let Some(settings) = settings {
+            ensure!(supply == 0, ContractError::SubjectSettingsNotAllowed {});
+            save_subject_settings(deps.storage, subject.clone(), settings)?;
+        }
 
-        let protocol_fee = price * protocol_fee_percent;
-        let subject_fee = price * subject_fee_percent;
+        let trade = simulate_buy(deps.as_ref(), &subject, &recipient, amount)?;
+        let total = trade.total;
 
-        let expected_payment = (price + protocol_fee + subject_fee).into();
+        if let Some(max_total_cost) = max_total_cost {
+            ensure!(
+                total <= max_total_cost,
+                ContractError::SlippageExceeded {
+                    expected: max_total_cost.u128(),
+                    actual: total.u128(),
+                }
+            );
+        }
         ensure!(
-            payment >= expected_payment,
+            payment >= total,
             ContractError::NotEnoughFunds {
-                expected: expected_payment,
-                actual: payment,
+                expected: total.u128(),
+                actual: payment.u128(),
             }
         );
 
-        increment_shares(deps.storage, subject.clone(), info.sender.clone(), amount)?;
+        let fees = record_buy(
+            deps.storage,
+            &env,
+            &subject,
+            &recipient,
+            referrer.as_ref(),
+            amount,
+            &trade,
+        )?;
 
         let mut res = Response::new();
+        if !fees.burn.is_zero() {
+            res = res.add_message(burn_msg(fees.burn, &denom));
+        }
 
-        if !protocol_fee.is_zero() {
-            let protocol_fee_msg = send_msg(&protocol_fee_destination, protocol_fee);
-            let subject_fee_msg = send_msg(&subject, subject_fee);
-            res = res.add_messages(vec![protocol_fee_msg, subject_fee_msg]);
+        // return anything paid above the expected cost to the buyer
+        let refund = payment - total;
+        if !refund.is_zero() {
+            res = res.add_message(send_msg(&info.sender, refund, &denom));
         }
 
         let event = TradeEvent::new(
             info.sender,
+            recipient,
             subject,
             true,
             amount,
-            price,
-            protocol_fee,
-            subject_fee,
-            supply + amount.u128(),
-        );
+            trade.price,
+            trade.protocol_fee,
+            trade.subject_fee,
+            refund,
+            trade.supply.u128(),
+        )
+        .with_referral(referrer, fees.referral)
+        .with_burn(fees.burn);
 
         Ok(res.add_event(event.into()))
     }
 
+    pub fn buy_shares_with_funds(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        subject: String,
+        min_amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        let subject_addr = deps.api.addr_validate(&subject)?;
+        let payment = must_pay(&info, &CONFIG.load(deps.storage)?.denom)?;
+        let amount = max_buy_for_funds(deps.as_ref(), &subject_addr, payment)?;
+
+        ensure!(
+            amount >= min_amount,
+            ContractError::SlippageExceeded {
+                expected: min_amount.u128(),
+                actual: amount.u128(),
+            }
+        );
+
+        // the unspent remainder is refunded like any overpayment
+        buy_shares(
+            deps, env, info, subject, amount, None, None, None, None, None,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn sell_shares(
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
         subject: String,
         amount: u128,
+        recipient: Option<String>,
+        referrer: Option<String>,
+        min_proceeds: Option<Uint128>,
+        deadline: Option<Expiration>,
+        msg: Option<Binary>,
     ) -> Result<Response, ContractError> {
         nonpayable(&info)?;
+        check_deadline(&env, deadline)?;
 
         let subject = deps.api.addr_validate(&subject)?;
-        let supply = load_supply(deps.storage, subject.clone())?;
+        let recipient = recipient
+            .map(|recipient| deps.api.addr_validate(&recipient))
+            .transpose()?
+            .unwrap_or_else(|| info.sender.clone());
+        ensure!(
+            recipient != env.contract.address,
+            ContractError::InvalidRecipient {}
+        );
+        let referrer = validate_referrer(deps.as_ref(), referrer, &info.sender, &recipient)?;
+        ensure_trading(deps.as_ref(), &subject, false)?;
+
+        let trade = simulate_sell(deps.as_ref(), &subject, &info.sender, amount.into())?;
+        let proceeds = trade.total;
 
-        ensure!(supply > amount, ContractError::LastShare {});
+        if let Some(min_proceeds) = min_proceeds {
+            ensure!(
+                proceeds >= min_proceeds,
+                ContractError::SlippageExceeded {
+                    expected: min_proceeds.u128(),
+                    actual: proceeds.u128(),
+                }
+            );
+        }
+
+        let fees = record_sell(
+            deps.storage,
+            &subject,
+            &info.sender,
+            referrer.as_ref(),
+            amount.into(),
+            &trade,
+        )?;
+
+        let denom = CONFIG.load(deps.storage)?.denom;
+        let mut res = Response::new();
+        if !fees.burn.is_zero() {
+            res = res.add_message(burn_msg(fees.burn, &denom));
+        }
+        match msg {
+            // the callback runs even without proceeds so integrations always hear back
+            Some(msg) => {
+                let funds = if proceeds.is_zero() {
+                    vec![]
+                } else {
+                    coins(proceeds.u128(), denom)
+                };
+                let callback = ReceiveMsg::SellProceeds {
+                    seller: info.sender.to_string(),
+                    subject: subject.to_string(),
+                    amount: proceeds,
+                    msg,
+                };
+                res = res.add_message(WasmMsg::Execute {
+                    contract_addr: recipient.to_string(),
+                    msg: to_binary(&callback)?,
+                    funds,
+                });
+            }
+            None if !proceeds.is_zero() => {
+                res = res.add_message(send_msg(&recipient, proceeds, &denom));
+            }
+            None => {}
+        }
+
+        let event = TradeEvent::new(
+            info.sender,
+            recipient,
+            subject,
+            false,
+            amount,
+            trade.price,
+            trade.protocol_fee,
+            trade.subject_fee,
+            Uint128::zero(),
+            trade.supply.u128(),
+        )
+        .with_referral(referrer, fees.referral)
+        .with_burn(fees.burn);
+
+        Ok(res.add_event(event.into()))
+    }
+
+    pub fn sell_all(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        subject: String,
+        min_proceeds: Option<Uint128>,
+    ) -> Result<Response, ContractError> {
+        let subject_addr = deps.api.addr_validate(&subject)?;
+        ensure!(
+            SHARES_BALANCE.has(deps.storage, (subject_addr.clone(), info.sender.clone())),
+            ContractError::NotEnoughShares {}
+        );
+
+        let amount = sellable_balance(deps.storage, subject_addr, info.sender.clone())?;
+        ensure!(!amount.is_zero(), ContractError::LastShare {});
+
+        sell_shares(
+            deps,
+            env,
+            info,
+            subject,
+            amount.u128(),
+            None,
+            None,
+            min_proceeds,
+            None,
+            None,
+        )
+    }
+
+    pub fn batch(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        trades: Vec<Trade>,
+    ) -> Result<Response, ContractError> {
+        ensure!(!trades.is_empty(), ContractError::EmptyBatch {});
+        let denom = CONFIG.load(deps.storage)?.denom;
+        let payment = may_pay(&info, &denom)?;
+
+        let mut cost = Uint128::zero();
+        let mut proceeds = Uint128::zero();
+        let mut burn = Uint128::zero();
+        let mut events = Vec::with_capacity(trades.len());
+
+        // legs run in order so later legs see the shares and supply of earlier ones
+        for leg in trades {
+            let (subject, amount, is_buy) = match leg {
+                Trade::Buy { subject, amount } => (subject, amount, true),
+                Trade::Sell { subject, amount } => (subject, amount, false),
+            };
+            let subject = deps.api.addr_validate(&subject)?;
+            ensure_trading(deps.as_ref(), &subject, is_buy)?;
+
+            let (trade, fees) = if is_buy {
+                let trade = simulate_buy(deps.as_ref(), &subject, &info.sender, amount)?;
+                let fees = record_buy(
+                    deps.storage,
+                    &env,
+                    &subject,
+                    &info.sender,
+                    None,
+                    amount,
+                    &trade,
+                )?;
+                cost = cost.checked_add(trade.total)?;
+                (trade, fees)
+            } else {
+                let trade = simulate_sell(deps.as_ref(), &subject, &info.sender, amount)?;
+                let fees = record_sell(deps.storage, &subject, &info.sender, None, amount, &trade)?;
+                proceeds = proceeds.checked_add(trade.total)?;
+                (trade, fees)
+            };
+            burn = burn.checked_add(fees.burn)?;
+
+            let event = TradeEvent::new(
+                info.sender.clone(),
+                info.sender.clone(),
+                subject,
+                is_buy,
+                amount,
+                trade.price,
+                trade.protocol_fee,
+                trade.subject_fee,
+                Uint128::zero(),
+                trade.supply.u128(),
+            )
+            .with_burn(fees.burn);
+            events.push(event.into());
+        }
+
+        // buys are paid from the attached funds and the proceeds of the batch's sells
+        let available = payment.checked_add(proceeds)?;
+        ensure!(
+            available >= cost,
+            ContractError::NotEnoughFunds {
+                expected: cost.saturating_sub(proceeds).u128(),
+                actual: payment.u128(),
+            }
+        );
+
+        let mut res = Response::new().add_events(events);
+
+        // burns of all legs go out as one message
+        if !burn.is_zero() {
+            res = res.add_message(burn_msg(burn, &denom));
+        }
+
+        let payout = available - cost;
+        if !payout.is_zero() {
+            res = res.add_message(send_msg(&info.sender, payout, &denom));
+        }
+
+        Ok(res)
+    }
+
+    pub fn transfer_shares(
+        deps: DepsMut,
+        info: MessageInfo,
+        subject: String,
+        recipient: String,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let subject = deps.api.addr_validate(&subject)?;
+        let recipient = deps.api.addr_validate(&recipient)?;
+        move_shares(deps, &subject, &info.sender, &recipient, amount)?;
+
+        let event = TransferEvent {
+            subject: subject.to_string(),
+            owner: info.sender.to_string(),
+            recipient: recipient.to_string(),
+            amount,
+            spender: None,
+        };
+
+        Ok(Response::new().add_event(event.into()))
+    }
+
+    pub fn transfer_shares_from(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        subject: String,
+        owner: String,
+        recipient: String,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let subject = deps.api.addr_validate(&subject)?;
+        let owner = deps.api.addr_validate(&owner)?;
+        let recipient = deps.api.addr_validate(&recipient)?;
+
+        let key = (subject.clone(), owner.clone(), info.sender.clone());
+        let mut allowance = ALLOWANCES
+            .may_load(deps.storage, key.clone())?
+            .ok_or(ContractError::InsufficientAllowance {})?;
+        ensure!(
+            !allowance.expires.is_expired(&env.block),
+            ContractError::AllowanceExpired {}
+        );
+        allowance.amount = allowance
+            .amount
+            .checked_sub(amount)
+            .map_err(|_| ContractError::InsufficientAllowance {})?;
+        ALLOWANCES.save(deps.storage, key, &allowance)?;
+
+        move_shares(deps, &subject, &owner, &recipient, amount)?;
+
+        let event = TransferEvent {
+            subject: subject.to_string(),
+            owner: owner.to_string(),
+            recipient: recipient.to_string(),
+            amount,
+            spender: Some(info.sender.to_string()),
+        };
+
+        Ok(Response::new().add_event(event.into()))
+    }
+
+    pub fn increase_allowance(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        subject: String,
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let subject = deps.api.addr_validate(&subject)?;
+        let spender = deps.api.addr_validate(&spender)?;
+        ensure!(
+            spender != info.sender,
+            ContractError::CannotSetOwnAccount {}
+        );
+
+        let key = (subject.clone(), info.sender, spender.clone());
+        let mut allowance = ALLOWANCES
+            .may_load(deps.storage, key.clone())?
+            // an expired allowance starts over
+            .filter(|allowance| !allowance.expires.is_expired(&env.block))
+            .unwrap_or(Allowance {
+                amount: Uint128::zero(),
+                expires: Expiration::Never {},
+            });
+        if let Some(expires) = expires {
+            ensure!(
+                !expires.is_expired(&env.block),
+                ContractError::AllowanceExpired {}
+            );
+            allowance.expires = expires;
+        }
+        allowance.amount = allowance.amount.checked_add(amount)?;
+        ALLOWANCES.save(deps.storage, key, &allowance)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "increase_allowance")
+            .add_attribute("subject", subject)
+            .add_attribute("spender", spender)
+            .add_attribute("amount", amount))
+    }
+
+    pub fn decrease_allowance(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        subject: String,
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let subject = deps.api.addr_validate(&subject)?;
+        let spender = deps.api.addr_validate(&spender)?;
+        ensure!(
+            spender != info.sender,
+            ContractError::CannotSetOwnAccount {}
+        );
+
+        let key = (subject.clone(), info.sender, spender.clone());
+        let mut allowance = ALLOWANCES
+            .may_load(deps.storage, key.clone())?
+            .ok_or(ContractError::InsufficientAllowance {})?;
+
+        if amount < allowance.amount {
+            if let Some(expires) = expires {
+                ensure!(
+                    !expires.is_expired(&env.block),
+                    ContractError::AllowanceExpired {}
+                );
+                allowance.expires = expires;
+            }
+            allowance.amount -= amount;
+            ALLOWANCES.save(deps.storage, key, &allowance)?;
+        } else {
+            ALLOWANCES.remove(deps.storage, key);
+        }
+
+        Ok(Response::new()
+            .add_attribute("method", "decrease_allowance")
+            .add_attribute("subject", subject)
+            .add_attribute("spender", spender)
+            .add_attribute("amount", amount))
+    }
+
+    pub fn set_transfers_enabled(
+        deps: DepsMut,
+        info: MessageInfo,
+        subject: String,
+        enabled: bool,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        only_admin(deps.as_ref(), &info.sender)?;
+
+        let subject = deps.api.addr_validate(&subject)?;
+        if enabled {
+            TRANSFERS_DISABLED.remove(deps.storage, subject.clone());
+        } else {
+            TRANSFERS_DISABLED.save(deps.storage, subject.clone(), &Empty {})?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("method", "set_transfers_enabled")
+            .add_attribute("subject", subject)
+            .add_attribute("enabled", enabled.to_string()))
+    }
+
+    pub fn claim_fees(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let fees = SUBJECT_FEES
+            .may_load(deps.storage, info.sender.clone())?
+            .unwrap_or_default();
+        ensure!(!fees.is_zero(), ContractError::NoFeesToClaim {});
+
+        SUBJECT_FEES.remove(deps.storage, info.sender.clone());
+
+        let denom = CONFIG.load(deps.storage)?.denom;
+
+        Ok(Response::new()
+            .add_message(send_msg(&info.sender, fees, &denom))
+            .add_attribute("method", "claim_fees")
+            .add_attribute("subject", info.sender)
+            .add_attribute("amount", fees))
+    }
+
+    pub fn claim_referral_fees(
+        deps: DepsMut,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let fees = REFERRAL_FEES
+            .may_load(deps.storage, info.sender.clone())?
+            .unwrap_or_default();
+        ensure!(!fees.is_zero(), ContractError::NoFeesToClaim {});
+
+        REFERRAL_FEES.remove(deps.storage, info.sender.clone());
+
+        let denom = CONFIG.load(deps.storage)?.denom;
+
+        Ok(Response::new()
+            .add_message(send_msg(&info.sender, fees, &denom))
+            .add_attribute("method", "claim_referral_fees")
+            .add_attribute("referrer", info.sender)
+            .add_attribute("amount", fees))
+    }
+
+    pub fn withdraw_protocol_fees(
+        deps: DepsMut,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
 
         let Config {
-            protocol_fee_destination,
-            protocol_fee_percent,
-            subject_fee_percent,
-            curve_coefficient,
+            protocol_fee_recipients,
+            denom,
+            ..
         } = CONFIG.load(deps.storage)?;
 
-        let price = price(supply - amount, amount, curve_coefficient);
+        // recipients removed from the split can still withdraw what they accrued
+        let fees = PROTOCOL_FEES.may_load(deps.storage, info.sender.clone())?;
+        ensure!(
+            fees.is_some()
+                || protocol_fee_recipients
+                    .iter()
+                    .any(|recipient| recipient.address == info.sender),
+            ContractError::Unauthorized {}
+        );
+        let fees = fees.unwrap_or_default();
+        ensure!(!fees.is_zero(), ContractError::NoFeesToClaim {});
+
+        PROTOCOL_FEES.remove(deps.storage, info.sender.clone());
+
+        Ok(Response::new()
+            .add_message(send_msg(&info.sender, fees, &denom))
+            .add_attribute("method", "withdraw_protocol_fees")
+            .add_attribute("recipient", info.sender)
+            .add_attribute("amount", fees))
+    }
+
+    pub fn migrate_indexes(
+        deps: DepsMut,
+        info: MessageInfo,
+        limit: Option<u32>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        only_admin(deps.as_ref(), &info.sender)?;
+
+        let limit = limit
+            .unwrap_or(DEFAULT_MIGRATION_LIMIT)
+            .min(MAX_MIGRATION_LIMIT) as usize;
+        let (visited, done) = crate::migrations::v0_2_0::backfill_indexes(deps.storage, limit)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "migrate_indexes")
+            .add_attribute("visited", visited.to_string())
+            .add_attribute("done", done.to_string()))
+    }
+
+    pub fn update_protocol_fee_recipients(
+        deps: DepsMut,
+        info: MessageInfo,
+        recipients: Vec<ProtocolFeeRecipient>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        only_admin(deps.as_ref(), &info.sender)?;
+
+        let mut config = CONFIG.load(deps.storage)?;
+        config.protocol_fee_recipients = validate_fee_recipients(deps.as_ref(), recipients)?;
+        CONFIG.save(deps.storage, &config)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "update_protocol_fee_recipients")
+            .add_attribute("sender", info.sender))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_config(
+        deps: DepsMut,
+        info: MessageInfo,
+        protocol_fee_bps: Option<u64>,
+        subject_fee_bps: Option<u64>,
+        referral_fee_bps: Option<u64>,
+        burn_bps: Option<u64>,
+        curve: Option<CurveConfig>,
+        subject_bounds: Option<SubjectBounds>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        only_admin(deps.as_ref(), &info.sender)?;
+
+        let mut config = CONFIG.load(deps.storage)?;
+
+        if let Some(bps) = protocol_fee_bps {
+            config.protocol_fee_percent = fee_percent(bps)?;
+        }
+        if let Some(bps) = subject_fee_bps {
+            config.subject_fee_percent = fee_percent(bps)?;
+        }
+        if let Some(bps) = referral_fee_bps {
+            config.referral_fee_percent = share_percent(bps)?;
+        }
+        if let Some(bps) = burn_bps {
+            config.burn_percent = share_percent(bps)?;
+        }
+        if let Some(curve) = curve {
+            config.curve = validate_curve(curve)?;
+        }
+        if let Some(bounds) = subject_bounds {
+            config.subject_bounds = Some(validate_bounds(bounds)?);
+        }
+
+        CONFIG.save(deps.storage, &config)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "update_config")
+            .add_attribute("sender", info.sender))
+    }
+
+    pub fn propose_new_admin(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        new_admin: String,
+        expiry: Option<Expiration>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        only_admin(deps.as_ref(), &info.sender)?;
+
+        let address = deps.api.addr_validate(&new_admin)?;
+        if let Some(expiry) = expiry {
+            ensure!(
+                !expiry.is_expired(&env.block),
+                ContractError::AdminProposalExpired {}
+            );
+        }
+
+        PENDING_ADMIN.save(deps.storage, &PendingAdmin { address, expiry })?;
+
+        Ok(Response::new()
+            .add_attribute("method", "propose_new_admin")
+            .add_attribute("admin", info.sender)
+            .add_attribute("pending_admin", new_admin))
+    }
+
+    pub fn accept_admin(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let PendingAdmin { address, expiry } = PENDING_ADMIN
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoPendingAdmin {})?;
+
+        ensure!(address == info.sender, ContractError::Unauthorized {});
+        if let Some(expiry) = expiry {
+            ensure!(
+                !expiry.is_expired(&env.block),
+                ContractError::AdminProposalExpired {}
+            );
+        }
+
+        ADMIN.save(deps.storage, &address)?;
+        PENDING_ADMIN.remove(deps.storage);
+
+        Ok(Response::new()
+            .add_attribute("method", "accept_admin")
+            .add_attribute("admin", address))
+    }
 
-        let protocol_fee = price * protocol_fee_percent;
-        let subject_fee = price * subject_fee_percent;
+    pub fn cancel_admin_proposal(
+        deps: DepsMut,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        only_admin(deps.as_ref(), &info.sender)?;
 
         ensure!(
-            SHARES_BALANCE.load(
-                deps.as_ref().storage,
-                (subject.clone(), info.sender.clone()),
-            )? >= amount.into(),
-            ContractError::NotEnoughShares {}
+            PENDING_ADMIN.exists(deps.storage),
+            ContractError::NoPendingAdmin {}
         );
+        PENDING_ADMIN.remove(deps.storage);
 
-        decrement_shares(deps.storage, subject.clone(), info.sender.clone(), amount)?;
+        Ok(Response::new().add_attribute("method", "cancel_admin_proposal"))
+    }
 
-        let sender_fee_msg = send_msg(&info.sender, price - protocol_fee - subject_fee);
-        let protocol_fee_msg = send_msg(&protocol_fee_destination, protocol_fee);
-        let subject_fee_msg = send_msg(&subject, subject_fee);
+    pub fn pause(
+        deps: DepsMut,
+        info: MessageInfo,
+        subject: Option<String>,
+        allow_sells: bool,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        only_admin(deps.as_ref(), &info.sender)?;
 
-        let event = TradeEvent::new(
-            info.sender,
+        let status = if allow_sells {
+            TradingStatus::SellsOnly
+        } else {
+            TradingStatus::Paused
+        };
+
+        let mut res = Response::new()
+            .add_attribute("method", "pause")
+            .add_attribute("sells_allowed", allow_sells.to_string());
+
+        match subject {
+            Some(subject) => {
+                let subject = deps.api.addr_validate(&subject)?;
+                SUBJECT_STATUS.save(deps.storage, subject.clone(), &status)?;
+                res = res.add_attribute("subject", subject);
+            }
+            None => TRADING_STATUS.save(deps.storage, &status)?,
+        }
+
+        Ok(res)
+    }
+
+    pub fn unpause(
+        deps: DepsMut,
+        info: MessageInfo,
+        subject: Option<String>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        only_admin(deps.as_ref(), &info.sender)?;
+
+        let mut res = Response::new().add_attribute("method", "unpause");
+
+        match subject {
+            Some(subject) => {
+                let subject = deps.api.addr_validate(&subject)?;
+                SUBJECT_STATUS.remove(deps.storage, subject.clone());
+                res = res.add_attribute("subject", subject);
+            }
+            None => TRADING_STATUS.save(deps.storage, &TradingStatus::Active)?,
+        }
+
+        Ok(res)
+    }
+
+    /// Stores the settings a subject picked with their first share, after
+    /// checking them against the admin's bounds.
+    fn save_subject_settings(
+        storage: &mut dyn Storage,
+        subject: Addr,
+        settings: SubjectSettings,
+    ) -> Result<(), ContractError> {
+        let config = CONFIG.load(storage)?;
+        let SubjectBounds {
+            min_curve_coefficient,
+            max_curve_coefficient,
+            max_subject_fee_bps,
+        } = config
+            .subject_bounds
+            .ok_or(ContractError::SubjectSettingsOutOfBounds {})?;
+
+        ensure!(
+            settings.curve_coefficient >= min_curve_coefficient
+                && settings.curve_coefficient <= max_curve_coefficient
+                && settings.subject_fee_bps <= max_subject_fee_bps,
+            ContractError::SubjectSettingsOutOfBounds {}
+        );
+
+        SUBJECT_CONFIG.save(
+            storage,
             subject,
-            false,
+            &SubjectConfig {
+                curve: config.curve.with_coefficient(settings.curve_coefficient),
+                subject_fee_percent: Some(Decimal::bps(settings.subject_fee_bps)),
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// Applies a priced buy: activates the subject on its first share, credits
+    /// the shares and accrues the fees.
+    fn record_buy(
+        storage: &mut dyn Storage,
+        env: &Env,
+        subject: &Addr,
+        trader: &Addr,
+        referrer: Option<&Addr>,
+        amount: Uint128,
+        trade: &TradeSimulation,
+    ) -> StdResult<FeeSplit> {
+        if trade.supply == amount {
+            let subject_info = SubjectInfo {
+                created_at: env.block.height,
+                holder_count: 0,
+            };
+            SUBJECTS.save(storage, subject.clone(), &subject_info)?;
+
+            // the first share fixes the curve the subject trades on from now on
+            if !SUBJECT_CONFIG.has(storage, subject.clone()) {
+                let subject_config = SubjectConfig {
+                    curve: CONFIG.load(storage)?.curve,
+                    subject_fee_percent: None,
+                };
+                SUBJECT_CONFIG.save(storage, subject.clone(), &subject_config)?;
+            }
+        }
+
+        increment_shares(storage, subject.clone(), trader.clone(), amount)?;
+        accrue_trade_fees(storage, subject, referrer, trade)
+    }
+
+    /// Applies a priced sell: debits the shares and accrues the fees.
+    fn record_sell(
+        storage: &mut dyn Storage,
+        subject: &Addr,
+        trader: &Addr,
+        referrer: Option<&Addr>,
+        amount: Uint128,
+        trade: &TradeSimulation,
+    ) -> StdResult<FeeSplit> {
+        decrement_shares(storage, subject.clone(), trader.clone(), amount)?;
+        accrue_trade_fees(storage, subject, referrer, trade)
+    }
+
+    /// Accrues the trade's fees. The referrer's cut comes out of the protocol
+    /// fee first, then part of what is left is burned.
+    fn accrue_trade_fees(
+        storage: &mut dyn Storage,
+        subject: &Addr,
+        referrer: Option<&Addr>,
+        trade: &TradeSimulation,
+    ) -> StdResult<FeeSplit> {
+        let config = CONFIG.load(storage)?;
+
+        let referral = match referrer {
+            Some(referrer) => {
+                let fee = trade.protocol_fee * config.referral_fee_percent;
+                accrue_referral_fee(storage, referrer.clone(), trade.price, fee)?;
+                fee
+            }
+            None => Uint128::zero(),
+        };
+        let protocol_fee = trade.protocol_fee - referral;
+        let burn = protocol_fee * config.burn_percent;
+
+        accrue_fees(
+            storage,
+            &config.protocol_fee_recipients,
+            subject.clone(),
+            protocol_fee - burn,
+            trade.subject_fee,
+        )?;
+
+        Ok(FeeSplit { referral, burn })
+    }
+
+    fn burn_msg(amount: Uint128, denom: &str) -> BankMsg {
+        BankMsg::Burn {
+            amount: coins(amount.u128(), denom),
+        }
+    }
+
+    /// Neither the trader nor the recipient of the trade can be the referrer.
+    /// A second wallet of the trader can not be told apart from any other
+    /// referrer, so that rebate is accepted.
+    fn validate_referrer(
+        deps: Deps,
+        referrer: Option<String>,
+        trader: &Addr,
+        recipient: &Addr,
+    ) -> Result<Option<Addr>, ContractError> {
+        match referrer {
+            Some(referrer) => {
+                let referrer = deps.api.addr_validate(&referrer)?;
+                ensure!(
+                    referrer != *trader && referrer != *recipient,
+                    ContractError::SelfReferral {}
+                );
+                Ok(Some(referrer))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn move_shares(
+        deps: DepsMut,
+        subject: &Addr,
+        owner: &Addr,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> Result<(), ContractError> {
+        ensure!(!amount.is_zero(), ContractError::InvalidAmount {});
+        ensure_migrated(deps.as_ref())?;
+        ensure!(
+            !TRANSFERS_DISABLED.has(deps.storage, subject.clone()),
+            ContractError::TransfersDisabled {}
+        );
+        ensure!(
+            SHARES_BALANCE
+                .may_load(deps.storage, (subject.clone(), owner.clone()))?
+                .unwrap_or_default()
+                >= amount,
+            ContractError::NotEnoughShares {}
+        );
+
+        Ok(crate::state::transfer_shares(
+            deps.storage,
+            subject,
+            owner,
+            recipient,
             amount,
-            price,
-            protocol_fee,
-            subject_fee,
-            supply - amount,
+        )?)
+    }
+
+    fn send_msg(to_address: &Addr, amount: Uint128, denom: &str) -> BankMsg {
+        BankMsg::Send {
+            to_address: to_address.to_string(),
+            amount: coins(amount.u128(), denom),
+        }
+    }
+
+    /// Both the global and the subject's status must allow the trade.
+    fn ensure_trading(deps: Deps, subject: &Addr, is_buy: bool) -> Result<(), ContractError> {
+        ensure_migrated(deps)?;
+        ensure!(
+            load_trading_status(deps.storage)?.allows(is_buy)
+                && load_subject_status(deps.storage, subject.clone())?.allows(is_buy),
+            ContractError::Paused {}
+        );
+        Ok(())
+    }
+
+    /// Balances can not move while the share indexes are being rebuilt.
+    fn ensure_migrated(deps: Deps) -> Result<(), ContractError> {
+        ensure!(
+            !INDEX_BACKFILL.exists(deps.storage),
+            ContractError::MigrationPending {}
         );
+        Ok(())
+    }
 
-        Ok(Response::new().add_event(event.into()).add_messages(vec![
-            sender_fee_msg,
-            protocol_fee_msg,
-            subject_fee_msg,
-        ]))
+    fn check_deadline(env: &Env, deadline: Option<Expiration>) -> Result<(), ContractError> {
+        if let Some(deadline) = deadline {
+            ensure!(
+                !deadline.is_expired(&env.block),
+                ContractError::DeadlineExceeded {
+                    deadline: deadline.to_string()
+                }
+            );
+        }
+        Ok(())
+    }
+
+    fn only_admin(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+        ensure!(
+            ADMIN.load(deps.storage)? == *sender,
+            ContractError::Unauthorized {}
+        );
+        Ok(())
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    let res = match msg {
         QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Ownership {} => to_binary(&query::ownership(deps)?),
+        QueryMsg::PauseStatus { subject } => to_binary(&query::pause_status(deps, subject)?),
+        QueryMsg::PendingFees { address } => to_binary(&query::pending_fees(deps, address)?),
+        QueryMsg::ReferralStats { referrer } => to_binary(&query::referral_stats(deps, referrer)?),
+        QueryMsg::SubjectConfig { subject } => to_binary(&query::subject_config(deps, subject)?),
         QueryMsg::SharesBalance { subject, holder } => {
             to_binary(&query::shares_balance(deps, subject, holder)?)
         }
         QueryMsg::SharesSupply { subject } => to_binary(&query::shares_supply(deps, subject)?),
+        QueryMsg::Allowance {
+            subject,
+            owner,
+            spender,
+        } => to_binary(&query::allowance(deps, subject, owner, spender)?),
+        QueryMsg::TransfersEnabled { subject } => {
+            to_binary(&query::transfers_enabled(deps, subject)?)
+        }
+        QueryMsg::Holders {
+            subject,
+            start_after,
+            limit,
+        } => to_binary(&query::holders(deps, subject, start_after, limit)?),
+        QueryMsg::Portfolio {
+            holder,
+            start_after,
+            limit,
+        } => to_binary(&query::portfolio(deps, holder, start_after, limit)?),
+        QueryMsg::Subjects {
+            start_after,
+            limit,
+            order_by,
+        } => to_binary(&query::subjects(deps, start_after, limit, order_by)?),
+        QueryMsg::SimulateBuy {
+            subject,
+            trader,
+            amount,
+        } => to_binary(&query::simulate_buy(deps, subject, trader, amount)?),
+        QueryMsg::SimulateSell {
+            subject,
+            trader,
+            amount,
+        } => to_binary(&query::simulate_sell(deps, subject, trader, amount)?),
+        QueryMsg::MaxBuyForFunds { subject, funds } => {
+            to_binary(&query::max_buy_for_funds(deps, subject, funds)?)
+        }
         QueryMsg::BuyPrice { subject, amount } => {
             to_binary(&query::buy_price(deps, subject, amount)?)
         }
@@ -202,14 +1491,81 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::SellPriceAfterFee { subject, amount } => {
             to_binary(&query::sell_price_after_fee(deps, subject, amount)?)
         }
-    }
+    };
+
+    Ok(res?)
 }
 
 pub mod query {
     use super::*;
-    use crate::state::{SHARES_BALANCE, SHARES_SUPPLY};
-    use cosmwasm_std::{Coin, Uint128};
-    use sg_std::star;
+    use crate::msg::{
+        AllowanceResponse, Holder, HoldersResponse, OrderBy, OwnershipResponse,
+        PauseStatusResponse, PendingFeesResponse, PortfolioResponse, Position,
+        ReferralStatsResponse, SubjectSummary, SubjectsResponse,
+    };
+    use crate::state::{
+        load_subject_config, load_subject_status, load_trading_status, sellable_balance,
+        SubjectConfig, ADMIN, ALLOWANCES, HOLDER_SUBJECTS, PENDING_ADMIN, PROTOCOL_FEES,
+        REFERRAL_FEES, REFERRAL_STATS, SHARES_BALANCE, SHARES_SUPPLY, SUBJECTS, SUBJECT_CONFIG,
+        SUBJECT_FEES, TRANSFERS_DISABLED,
+    };
+    use cosmwasm_std::{Coin, Order, Uint128};
+    use cw_storage_plus::Bound;
+    use cw_utils::Expiration;
+
+    pub fn ownership(deps: Deps) -> StdResult<OwnershipResponse> {
+        let pending = PENDING_ADMIN.may_load(deps.storage)?;
+
+        Ok(OwnershipResponse {
+            admin: ADMIN.load(deps.storage)?,
+            pending_admin: pending.as_ref().map(|p| p.address.clone()),
+            pending_expiry: pending.and_then(|p| p.expiry),
+        })
+    }
+
+    pub fn pause_status(deps: Deps, subject: Option<String>) -> StdResult<PauseStatusResponse> {
+        let subject = subject
+            .map(|subject| load_subject_status(deps.storage, deps.api.addr_validate(&subject)?))
+            .transpose()?;
+
+        Ok(PauseStatusResponse {
+            global: load_trading_status(deps.storage)?,
+            subject,
+        })
+    }
+
+    pub fn pending_fees(deps: Deps, address: String) -> StdResult<PendingFeesResponse> {
+        let address = deps.api.addr_validate(&address)?;
+
+        Ok(PendingFeesResponse {
+            subject_fees: SUBJECT_FEES
+                .may_load(deps.storage, address.clone())?
+                .unwrap_or_default(),
+            protocol_fees: PROTOCOL_FEES
+                .may_load(deps.storage, address)?
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn referral_stats(deps: Deps, referrer: String) -> StdResult<ReferralStatsResponse> {
+        let referrer = deps.api.addr_validate(&referrer)?;
+        let stats = REFERRAL_STATS
+            .may_load(deps.storage, referrer.clone())?
+            .unwrap_or_default();
+
+        Ok(ReferralStatsResponse {
+            trade_count: stats.trade_count,
+            volume: stats.volume,
+            earned: stats.earned,
+            pending: REFERRAL_FEES
+                .may_load(deps.storage, referrer)?
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn subject_config(deps: Deps, subject: String) -> StdResult<Option<SubjectConfig>> {
+        SUBJECT_CONFIG.may_load(deps.storage, deps.api.addr_validate(&subject)?)
+    }
 
     pub fn shares_balance(deps: Deps, subject: String, holder: String) -> StdResult<Uint128> {
         let balance = SHARES_BALANCE
@@ -233,68 +1589,227 @@ pub mod query {
         Ok(supply)
     }
 
-    pub fn buy_price(deps: Deps, subject: String, amount: Uint128) -> StdResult<Coin> {
-        let coefficient = CONFIG.load(deps.storage)?.curve_coefficient;
-        let supply = SHARES_SUPPLY.load(deps.storage, deps.api.addr_validate(&subject)?)?;
+    pub fn allowance(
+        deps: Deps,
+        subject: String,
+        owner: String,
+        spender: String,
+    ) -> StdResult<AllowanceResponse> {
+        let allowance = ALLOWANCES
+            .may_load(
+                deps.storage,
+                (
+                    deps.api.addr_validate(&subject)?,
+                    deps.api.addr_validate(&owner)?,
+                    deps.api.addr_validate(&spender)?,
+                ),
+            )?
+            .map(|allowance| AllowanceResponse {
+                allowance: allowance.amount,
+                expires: allowance.expires,
+            })
+            .unwrap_or(AllowanceResponse {
+                allowance: Uint128::zero(),
+                expires: Expiration::Never {},
+            });
+
+        Ok(allowance)
+    }
 
-        Ok(star(price(supply, amount, coefficient)))
+    pub fn transfers_enabled(deps: Deps, subject: String) -> StdResult<bool> {
+        Ok(!TRANSFERS_DISABLED.has(deps.storage, deps.api.addr_validate(&subject)?))
     }
 
-    pub fn sell_price(deps: Deps, subject: String, amount: Uint128) -> StdResult<Coin> {
-        let coefficient = CONFIG.load(deps.storage)?.curve_coefficient;
-        let supply = SHARES_SUPPLY.load(deps.storage, deps.api.addr_validate(&subject)?)?;
+    pub fn holders(
+        deps: Deps,
+        subject: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<HoldersResponse> {
+        let subject = deps.api.addr_validate(&subject)?;
+        let start_after = start_after
+            .map(|holder| deps.api.addr_validate(&holder))
+            .transpose()?;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
 
-        Ok(star(price(supply - amount, amount, coefficient)))
+        let holders = SHARES_BALANCE
+            .prefix(subject)
+            .range(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            // 0.1.0 left empty balances behind until the indexes are rebuilt
+            .filter(|item| !matches!(item, Ok((_, balance)) if balance.is_zero()))
+            .take(limit)
+            .map(|item| {
+                let (address, balance) = item?;
+                Ok(Holder { address, balance })
+            })
+            .collect::<StdResult<_>>()?;
+
+        Ok(HoldersResponse { holders })
     }
 
-    pub fn buy_price_after_fee(deps: Deps, subject: String, amount: Uint128) -> StdResult<Coin> {
-        let Config {
-            protocol_fee_percent,
-            subject_fee_percent,
-            ..
-        } = CONFIG.load(deps.storage)?;
+    pub fn portfolio(
+        deps: Deps,
+        holder: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<PortfolioResponse, ContractError> {
+        let holder = deps.api.addr_validate(&holder)?;
+        let start_after = start_after
+            .map(|subject| deps.api.addr_validate(&subject))
+            .transpose()?;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
 
-        let price = buy_price(deps, subject, amount)?;
+        let subjects = HOLDER_SUBJECTS
+            .prefix(holder.clone())
+            .keys(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
 
-        let protocol_fee = price.amount * protocol_fee_percent;
-        let subject_fee = price.amount * subject_fee_percent;
+        let positions = subjects
+            .into_iter()
+            .map(|subject| {
+                let balance =
+                    SHARES_BALANCE.load(deps.storage, (subject.clone(), holder.clone()))?;
+                let sellable = sellable_balance(deps.storage, subject.clone(), holder.clone())?;
+                let sell_value = if sellable.is_zero() {
+                    Uint128::zero()
+                } else {
+                    sell_price_after_fee(deps, subject.to_string(), sellable)?.amount
+                };
+                Ok(Position {
+                    subject,
+                    balance,
+                    sell_value,
+                })
+            })
+            .collect::<Result<_, ContractError>>()?;
 
-        Ok(star(price.amount + protocol_fee + subject_fee))
+        Ok(PortfolioResponse { positions })
     }
 
-    pub fn sell_price_after_fee(deps: Deps, subject: String, amount: Uint128) -> StdResult<Coin> {
-        let Config {
-            protocol_fee_percent,
-            subject_fee_percent,
-            ..
-        } = CONFIG.load(deps.storage)?;
+    pub fn subjects(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order_by: Option<OrderBy>,
+    ) -> StdResult<SubjectsResponse> {
+        let start_after = start_after
+            .map(|subject| deps.api.addr_validate(&subject))
+            .transpose()?
+            .map(Bound::exclusive);
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
 
-        let price = sell_price(deps, subject, amount)?;
+        let (min, max, order) = match order_by.unwrap_or(OrderBy::Ascending) {
+            OrderBy::Ascending => (start_after, None, Order::Ascending),
+            OrderBy::Descending => (None, start_after, Order::Descending),
+        };
 
-        let protocol_fee = price.amount * protocol_fee_percent;
-        let subject_fee = price.amount * subject_fee_percent;
+        let subjects = SUBJECTS
+            .range(deps.storage, min, max, order)
+            .take(limit)
+            .map(|item| {
+                let (subject, info) = item?;
+                Ok(SubjectSummary {
+                    supply: SHARES_SUPPLY.load(deps.storage, subject.clone())?,
+                    subject,
+                    holder_count: info.holder_count,
+                    created_at: info.created_at,
+                })
+            })
+            .collect::<StdResult<_>>()?;
 
-        Ok(star(price.amount - protocol_fee - subject_fee))
+        Ok(SubjectsResponse { subjects })
     }
-}
 
-/// Price of shares is based on a cubic polynomial function with a fixed coefficient.
-/// The first share can only be bought by the subject.
-pub fn price(supply: impl Into<u128>, amount: impl Into<u128>, coefficient: Decimal) -> Uint128 {
-    let (supply, amount) = (supply.into(), amount.into());
+    pub fn simulate_buy(
+        deps: Deps,
+        subject: String,
+        trader: String,
+        amount: Uint128,
+    ) -> Result<TradeSimulation, ContractError> {
+        super::simulate_buy(
+            deps,
+            &deps.api.addr_validate(&subject)?,
+            &deps.api.addr_validate(&trader)?,
+            amount,
+        )
+    }
 
-    let sum = |x: u128| x * (x + 1) * (2 * x + 1) / 6;
+    pub fn simulate_sell(
+        deps: Deps,
+        subject: String,
+        trader: String,
+        amount: Uint128,
+    ) -> Result<TradeSimulation, ContractError> {
+        super::simulate_sell(
+            deps,
+            &deps.api.addr_validate(&subject)?,
+            &deps.api.addr_validate(&trader)?,
+            amount,
+        )
+    }
 
-    let sum1 = if supply == 0 { 0 } else { sum(supply - 1) };
+    pub fn max_buy_for_funds(
+        deps: Deps,
+        subject: String,
+        funds: Uint128,
+    ) -> Result<Uint128, ContractError> {
+        super::max_buy_for_funds(deps, &deps.api.addr_validate(&subject)?, funds)
+    }
 
-    let sum2 = if supply == 0 && amount == 1 {
-        0
-    } else {
-        sum(supply + amount - 1)
-    };
+    pub fn buy_price(deps: Deps, subject: String, amount: Uint128) -> Result<Coin, ContractError> {
+        let subject = deps.api.addr_validate(&subject)?;
+        let config = load_subject_config(deps.storage, subject.clone())?;
+        let supply = SHARES_SUPPLY.load(deps.storage, subject)?;
+
+        Ok(config.coin(config.curve.price(supply.u128(), amount.u128())?))
+    }
+
+    pub fn sell_price(deps: Deps, subject: String, amount: Uint128) -> Result<Coin, ContractError> {
+        let subject = deps.api.addr_validate(&subject)?;
+        let config = load_subject_config(deps.storage, subject.clone())?;
+        let supply = SHARES_SUPPLY.load(deps.storage, subject)?;
+        let remaining = supply
+            .checked_sub(amount)
+            .map_err(|_| ContractError::InsufficientSupply {})?;
+
+        Ok(config.coin(config.curve.price(remaining.u128(), amount.u128())?))
+    }
+
+    pub fn buy_price_after_fee(
+        deps: Deps,
+        subject: String,
+        amount: Uint128,
+    ) -> Result<Coin, ContractError> {
+        let config = load_subject_config(deps.storage, deps.api.addr_validate(&subject)?)?;
 
-    let summation = sum2.wrapping_sub(sum1);
-    let star = 1_000_000u128;
+        let price = buy_price(deps, subject, amount)?.amount;
+        let (protocol_fee, subject_fee) = config.fees(price);
 
-    Uint128::from(summation.wrapping_mul(star)) * coefficient
+        Ok(config.coin(price.checked_add(protocol_fee)?.checked_add(subject_fee)?))
+    }
+
+    pub fn sell_price_after_fee(
+        deps: Deps,
+        subject: String,
+        amount: Uint128,
+    ) -> Result<Coin, ContractError> {
+        let config = load_subject_config(deps.storage, deps.api.addr_validate(&subject)?)?;
+
+        let price = sell_price(deps, subject, amount)?.amount;
+        let (protocol_fee, subject_fee) = config.fees(price);
+
+        let proceeds = price.checked_sub(protocol_fee)?.checked_sub(subject_fee)?;
+        Ok(config.coin(proceeds))
+    }
 }
@@ -0,0 +1,245 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Decimal, Decimal256, Uint128, Uint256};
+
+use crate::error::ContractError;
+
+/// Micro units in one STARS.
+const STAR: u128 = 1_000_000;
+
+/// A bonding curve prices shares from the current supply. Share `i` is the
+/// share bought when the supply is `i`, so the first share is share `0`.
+pub trait BondingCurve {
+    /// Total price of buying `amount` shares on top of `supply`.
+    fn price(&self, supply: u128, amount: u128) -> Result<Uint128, ContractError>;
+
+    /// Checks the curve parameters.
+    fn validate(&self) -> Result<(), ContractError>;
+}
+
+#[cw_serde]
+pub enum CurveConfig {
+    Quadratic(Quadratic),
+    Linear(Linear),
+    Exponential(Exponential),
+    Sigmoid(Sigmoid),
+}
+
+impl CurveConfig {
+    /// The coefficient that scales the price of every share.
+    pub fn coefficient(&self) -> Decimal {
+        match self {
+            CurveConfig::Quadratic(curve) => curve.coefficient,
+            CurveConfig::Linear(curve) => curve.coefficient,
+            CurveConfig::Exponential(curve) => curve.coefficient,
+            CurveConfig::Sigmoid(curve) => curve.coefficient,
+        }
+    }
+
+    /// The same curve family and shape with a different coefficient.
+    pub fn with_coefficient(mut self, coefficient: Decimal) -> Self {
+        match &mut self {
+            CurveConfig::Quadratic(curve) => curve.coefficient = coefficient,
+            CurveConfig::Linear(curve) => curve.coefficient = coefficient,
+            CurveConfig::Exponential(curve) => curve.coefficient = coefficient,
+            CurveConfig::Sigmoid(curve) => curve.coefficient = coefficient,
+        }
+        self
+    }
+}
+
+impl BondingCurve for CurveConfig {
+    fn price(&self, supply: u128, amount: u128) -> Result<Uint128, ContractError> {
+        match self {
+            CurveConfig::Quadratic(curve) => curve.price(supply, amount),
+            CurveConfig::Linear(curve) => curve.price(supply, amount),
+            CurveConfig::Exponential(curve) => curve.price(supply, amount),
+            CurveConfig::Sigmoid(curve) => curve.price(supply, amount),
+        }
+    }
+
+    fn validate(&self) -> Result<(), ContractError> {
+        ensure!(
+            !self.coefficient().is_zero(),
+            ContractError::InvalidCurveCoefficient {}
+        );
+
+        match self {
+            CurveConfig::Quadratic(curve) => curve.validate(),
+            CurveConfig::Linear(curve) => curve.validate(),
+            CurveConfig::Exponential(curve) => curve.validate(),
+            CurveConfig::Sigmoid(curve) => curve.validate(),
+        }
+    }
+}
+
+/// Share `i` costs `coefficient * i^2` STARS.
+#[cw_serde]
+pub struct Quadratic {
+    pub coefficient: Decimal,
+}
+
+impl BondingCurve for Quadratic {
+    fn price(&self, supply: u128, amount: u128) -> Result<Uint128, ContractError> {
+        // sum of squares 0^2 + 1^2 + ... + x^2
+        let summation = range_sum(supply, amount, |x| {
+            let product = x
+                .checked_mul(x + Uint256::one())?
+                .checked_mul(x * Uint256::from(2u8) + Uint256::one())?;
+            Ok(product / Uint256::from(6u8))
+        })?;
+
+        scale_uint(summation, self.coefficient)
+    }
+
+    fn validate(&self) -> Result<(), ContractError> {
+        Ok(())
+    }
+}
+
+/// Share `i` costs `coefficient * i` STARS.
+#[cw_serde]
+pub struct Linear {
+    pub coefficient: Decimal,
+}
+
+impl BondingCurve for Linear {
+    fn price(&self, supply: u128, amount: u128) -> Result<Uint128, ContractError> {
+        // sum of integers 0 + 1 + ... + x
+        let summation = range_sum(supply, amount, |x| {
+            Ok(x.checked_mul(x + Uint256::one())? / Uint256::from(2u8))
+        })?;
+
+        scale_uint(summation, self.coefficient)
+    }
+
+    fn validate(&self) -> Result<(), ContractError> {
+        Ok(())
+    }
+}
+
+/// Share `i` costs `coefficient * ((1 + growth_rate)^i - 1)` STARS.
+#[cw_serde]
+pub struct Exponential {
+    pub coefficient: Decimal,
+    pub growth_rate: Decimal,
+}
+
+impl BondingCurve for Exponential {
+    fn price(&self, supply: u128, amount: u128) -> Result<Uint128, ContractError> {
+        ensure!(amount > 0, ContractError::InvalidAmount {});
+
+        let end = supply
+            .checked_add(amount)
+            .ok_or(ContractError::Overflow {})?;
+        let exponent = |x: u128| u32::try_from(x).map_err(|_| ContractError::Overflow {});
+
+        // geometric series g^supply + ... + g^(end - 1), minus 1 per share
+        let growth = Decimal256::from(self.growth_rate);
+        let base = Decimal256::one() + growth;
+        let series = base
+            .checked_pow(exponent(end)?)?
+            .checked_sub(base.checked_pow(exponent(supply)?)?)?
+            .checked_div(growth)
+            .map_err(|_| ContractError::Overflow {})?;
+        let summation = series.saturating_sub(decimal(amount)?);
+
+        scale_decimal(summation, self.coefficient)
+    }
+
+    fn validate(&self) -> Result<(), ContractError> {
+        ensure!(
+            !self.growth_rate.is_zero(),
+            ContractError::InvalidCurve {
+                reason: "growth rate must be greater than zero".to_string()
+            }
+        );
+        Ok(())
+    }
+}
+
+/// Share `i` costs `coefficient * (1 + (i - midpoint) / sqrt(smoothing + (i - midpoint)^2))`
+/// STARS, rising from zero towards `2 * coefficient` with the steepest growth at `midpoint`.
+/// Prices are the integral of the curve over the bought range.
+#[cw_serde]
+pub struct Sigmoid {
+    pub coefficient: Decimal,
+    pub midpoint: Uint128,
+    pub smoothing: Decimal,
+}
+
+impl Sigmoid {
+    /// Antiderivative of the curve without the linear term.
+    fn distance(&self, x: u128) -> Result<Decimal256, ContractError> {
+        let offset = decimal(x.abs_diff(self.midpoint.u128()))?;
+        Ok(Decimal256::from(self.smoothing)
+            .checked_add(offset.checked_mul(offset)?)?
+            .sqrt())
+    }
+}
+
+impl BondingCurve for Sigmoid {
+    fn price(&self, supply: u128, amount: u128) -> Result<Uint128, ContractError> {
+        ensure!(amount > 0, ContractError::InvalidAmount {});
+
+        let end = supply
+            .checked_add(amount)
+            .ok_or(ContractError::Overflow {})?;
+
+        let summation = decimal(amount)?
+            .checked_add(self.distance(end)?)?
+            .saturating_sub(self.distance(supply)?);
+
+        scale_decimal(summation, self.coefficient)
+    }
+
+    fn validate(&self) -> Result<(), ContractError> {
+        Ok(())
+    }
+}
+
+/// Sum of the terms `supply..supply + amount` given the cumulative sum of terms `0..=x`.
+fn range_sum(
+    supply: u128,
+    amount: u128,
+    cumulative: impl Fn(Uint256) -> Result<Uint256, ContractError>,
+) -> Result<Uint256, ContractError> {
+    ensure!(amount > 0, ContractError::InvalidAmount {});
+
+    let last = supply
+        .checked_add(amount - 1)
+        .ok_or(ContractError::Overflow {})?;
+
+    let upper = cumulative(last.into())?;
+    let lower = if supply == 0 {
+        Uint256::zero()
+    } else {
+        cumulative((supply - 1).into())?
+    };
+
+    Ok(upper.checked_sub(lower)?)
+}
+
+/// Converts a number of whole STARS into micro units scaled by the coefficient.
+fn scale_uint(summation: Uint256, coefficient: Decimal) -> Result<Uint128, ContractError> {
+    let price = summation
+        .checked_mul(STAR.into())?
+        .checked_mul(coefficient.atomics().into())?
+        / Uint256::from(Decimal::one().atomics());
+
+    Ok(price.try_into()?)
+}
+
+/// Converts a fractional number of STARS into micro units scaled by the coefficient.
+fn scale_decimal(summation: Decimal256, coefficient: Decimal) -> Result<Uint128, ContractError> {
+    let price = summation
+        .checked_mul(coefficient.into())?
+        .atomics()
+        .checked_mul(STAR.into())?
+        / Decimal256::one().atomics();
+
+    Ok(price.try_into()?)
+}
+
+fn decimal(x: u128) -> Result<Decimal256, ContractError> {
+    Decimal256::checked_from_ratio(x, 1u128).map_err(|_| ContractError::Overflow {})
+}
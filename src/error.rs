@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{ConversionOverflowError, OverflowError, StdError};
 use cw_utils::PaymentError;
 use thiserror::Error;
 
@@ -10,6 +10,9 @@ pub enum ContractError {
     #[error("{0}")]
     Payment(#[from] PaymentError),
 
+    #[error("semver parse error: {0}")]
+    SemVer(String),
+
     #[error("subject must be the first to buy shares: {subject:?}")]
     NotSubject { subject: String },
 
@@ -22,6 +25,105 @@ pub enum ContractError {
     #[error("not enough funds: {expected} got {actual}")]
     NotEnoughFunds { expected: u128, actual: u128 },
 
+    #[error("slippage exceeded: expected {expected} got {actual}")]
+    SlippageExceeded { expected: u128, actual: u128 },
+
+    #[error("trade deadline exceeded: {deadline}")]
+    DeadlineExceeded { deadline: String },
+
+    #[error("trading is paused")]
+    Paused {},
+
+    #[error("arithmetic overflow")]
+    Overflow {},
+
+    #[error("amount must be greater than zero")]
+    InvalidAmount {},
+
+    #[error("amount exceeds supply")]
+    InsufficientSupply {},
+
+    #[error("batch must contain at least one trade")]
+    EmptyBatch {},
+
+    #[error("subject settings can only be chosen by the subject with their first share")]
+    SubjectSettingsNotAllowed {},
+
+    #[error("subject settings are outside the allowed bounds")]
+    SubjectSettingsOutOfBounds {},
+
+    #[error("no fees to claim")]
+    NoFeesToClaim {},
+
+    #[error("proceeds can not be sent to this contract")]
+    InvalidRecipient {},
+
+    #[error("traders can not refer themselves or the recipient of the trade")]
+    SelfReferral {},
+
+    #[error("invalid protocol fee recipients: {reason}")]
+    InvalidFeeRecipients { reason: String },
+
+    #[error("invalid payment denom")]
+    InvalidDenom {},
+
+    #[error("share transfers are disabled for this subject")]
+    TransfersDisabled {},
+
+    #[error("allowance is too low")]
+    InsufficientAllowance {},
+
+    #[error("allowance is expired")]
+    AllowanceExpired {},
+
+    #[error("cannot set an allowance for your own account")]
+    CannotSetOwnAccount {},
+
     #[error("unauthorized")]
     Unauthorized {},
+
+    #[error("fee of {bps} bps exceeds maximum of {max} bps")]
+    FeeTooHigh { bps: u64, max: u64 },
+
+    #[error("curve coefficient must be greater than zero")]
+    InvalidCurveCoefficient {},
+
+    #[error("invalid curve: {reason}")]
+    InvalidCurve { reason: String },
+
+    #[error("no pending admin proposal")]
+    NoPendingAdmin {},
+
+    #[error("admin proposal expired")]
+    AdminProposalExpired {},
+
+    #[error("cannot migrate from contract {actual}, expected {expected}")]
+    WrongContract { expected: String, actual: String },
+
+    #[error("cannot migrate from version {stored} to older version {new}")]
+    CannotDowngrade { stored: String, new: String },
+
+    #[error("share indexes are still being migrated")]
+    MigrationPending {},
+
+    #[error("admin must be provided when migrating from a version without one")]
+    MissingAdmin {},
+}
+
+impl From<OverflowError> for ContractError {
+    fn from(_: OverflowError) -> Self {
+        Self::Overflow {}
+    }
+}
+
+impl From<ConversionOverflowError> for ContractError {
+    fn from(_: ConversionOverflowError) -> Self {
+        Self::Overflow {}
+    }
+}
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
 }
@@ -1,5 +1,7 @@
 pub mod contract;
+pub mod curve;
 mod error;
+mod migrations;
 pub mod msg;
 pub mod state;
 #[cfg(test)]
@@ -0,0 +1,156 @@
+//! State migrations, applied in order by the `migrate` entry point. Each step
+//! runs only when upgrading from a version older than the one it introduces.
+
+use cosmwasm_std::DepsMut;
+use semver::Version;
+
+use crate::error::ContractError;
+use crate::msg::MigrateMsg;
+
+pub fn migrate_state(
+    deps: DepsMut,
+    stored: &Version,
+    msg: &MigrateMsg,
+) -> Result<(), ContractError> {
+    if *stored < Version::new(0, 2, 0) {
+        v0_2_0::migrate(deps, msg)?;
+    }
+
+    Ok(())
+}
+
+/// 0.1.0 had no admin, so one must be supplied in the `MigrateMsg`, and priced
+/// every subject on the quadratic curve with a single `curve_coefficient`,
+/// always in ustars.
+/// It also kept empty balances around and had no holder to subject index or
+/// subject registry. Those are rebuilt afterwards in pages by the admin through
+/// `ExecuteMsg::MigrateIndexes`, since one pass over every balance would not fit
+/// in a block on a live market.
+pub(crate) mod v0_2_0 {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{ensure, Addr, Decimal, DepsMut, Empty, Order, StdResult, Storage, Uint128};
+    use cw_storage_plus::{Bound, Item};
+    use sg_std::NATIVE_DENOM;
+
+    use crate::contract::MAX_FEE_BPS;
+    use crate::curve::{CurveConfig, Quadratic};
+    use crate::error::ContractError;
+    use crate::msg::MigrateMsg;
+    use crate::state::{
+        Config, FeeRecipient, SubjectInfo, ADMIN, CONFIG, HOLDER_SUBJECTS, LEGACY_CURVE,
+        SHARES_BALANCE, SUBJECTS,
+    };
+
+    #[cw_serde]
+    pub struct ConfigV0_1 {
+        pub protocol_fee_destination: Addr,
+        pub protocol_fee_percent: Decimal,
+        pub subject_fee_percent: Decimal,
+        pub curve_coefficient: Decimal,
+    }
+
+    pub const CONFIG_V0_1: Item<ConfigV0_1> = Item::new("config");
+
+    /// Progress of the index rebuild, removed once every balance was visited.
+    #[cw_serde]
+    pub struct IndexBackfill {
+        pub start_after: Option<(Addr, Addr)>,
+    }
+
+    pub const INDEX_BACKFILL: Item<IndexBackfill> = Item::new("index_backfill");
+
+    pub fn migrate(deps: DepsMut, msg: &MigrateMsg) -> Result<(), ContractError> {
+        if !ADMIN.exists(deps.storage) {
+            let admin = msg.admin.as_ref().ok_or(ContractError::MissingAdmin {})?;
+            ADMIN.save(deps.storage, &deps.api.addr_validate(admin)?)?;
+        }
+
+        let old = CONFIG_V0_1.load(deps.storage)?;
+
+        let config = Config {
+            denom: NATIVE_DENOM.to_string(),
+            protocol_fee_recipients: vec![FeeRecipient {
+                address: old.protocol_fee_destination,
+                weight: Decimal::one(),
+            }],
+            protocol_fee_percent: capped_fee(old.protocol_fee_percent)?,
+            subject_fee_percent: capped_fee(old.subject_fee_percent)?,
+            referral_fee_percent: Decimal::zero(),
+            burn_percent: Decimal::zero(),
+            curve: CurveConfig::Quadratic(Quadratic {
+                coefficient: old.curve_coefficient,
+            }),
+            subject_bounds: None,
+        };
+        CONFIG.save(deps.storage, &config)?;
+        // existing subjects stay on this curve whatever the global curve becomes
+        LEGACY_CURVE.save(deps.storage, &config.curve)?;
+
+        INDEX_BACKFILL.save(deps.storage, &IndexBackfill { start_after: None })?;
+
+        Ok(())
+    }
+
+    /// 0.1.0 did not cap its fees, so they are held to `MAX_FEE_BPS` like any
+    /// fee set since.
+    fn capped_fee(percent: Decimal) -> Result<Decimal, ContractError> {
+        let bps = Uint128::new(10_000) * percent;
+        ensure!(
+            percent <= Decimal::bps(MAX_FEE_BPS),
+            ContractError::FeeTooHigh {
+                bps: u64::try_from(bps.u128()).unwrap_or(u64::MAX),
+                max: MAX_FEE_BPS,
+            }
+        );
+        Ok(percent)
+    }
+
+    /// Rebuilds the indexes for the next `limit` balances, dropping empty ones.
+    /// Returns the number of balances visited and whether the rebuild is done.
+    pub fn backfill_indexes(
+        storage: &mut dyn Storage,
+        limit: usize,
+    ) -> Result<(usize, bool), ContractError> {
+        let mut backfill = match INDEX_BACKFILL.may_load(storage)? {
+            Some(backfill) => backfill,
+            None => return Ok((0, true)),
+        };
+
+        let balances = SHARES_BALANCE
+            .range(
+                storage,
+                backfill.start_after.clone().map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
+        let visited = balances.len();
+
+        for ((subject, holder), balance) in balances {
+            backfill.start_after = Some((subject.clone(), holder.clone()));
+
+            if balance.is_zero() {
+                SHARES_BALANCE.remove(storage, (subject, holder));
+                continue;
+            }
+
+            HOLDER_SUBJECTS.save(storage, (holder, subject.clone()), &Empty {})?;
+            // the first share's block height was never recorded
+            SUBJECTS.update(storage, subject, |info| -> StdResult<_> {
+                let mut info: SubjectInfo = info.unwrap_or_default();
+                info.holder_count += 1;
+                Ok(info)
+            })?;
+        }
+
+        let done = visited < limit;
+        if done {
+            INDEX_BACKFILL.remove(storage);
+        } else {
+            INDEX_BACKFILL.save(storage, &backfill)?;
+        }
+
+        Ok((visited, done))
+    }
+}
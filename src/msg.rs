@@ -1,20 +1,171 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Coin, Decimal, Event, Uint128};
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, Event, Uint128};
+use cw_utils::Expiration;
 
-use crate::state::Config;
+use crate::curve::CurveConfig;
+use crate::state::{Config, SubjectBounds, SubjectConfig, TradingStatus};
 
 #[cw_serde]
 pub struct InstantiateMsg {
-    pub protocol_fee_destination: String,
+    /// Denom that trades are paid in, `ustars` if not set.
+    pub denom: Option<String>,
+    /// Weights must add up to 10_000 bps.
+    pub protocol_fee_recipients: Vec<ProtocolFeeRecipient>,
     pub protocol_fee_bps: u64,
     pub subject_fee_bps: u64,
-    pub curve_coefficient: Decimal,
+    /// Share of the protocol fee paid to referrers, in bps of the protocol fee.
+    pub referral_fee_bps: u64,
+    /// Share of the protocol fee left after referrals that is burned, in bps.
+    pub burn_bps: u64,
+    pub curve: CurveConfig,
+    pub subject_bounds: Option<SubjectBounds>,
+}
+
+#[cw_serde]
+pub struct ProtocolFeeRecipient {
+    pub address: String,
+    /// Share of the protocol fee in bps.
+    pub weight_bps: u64,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {
+    /// Admin to set when migrating from a version that predates the admin role.
+    pub admin: Option<String>,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
-    BuyShares { subject: String, amount: Uint128 },
-    SellShares { subject: String, amount: Uint128 },
+    /// Fails if the total cost including fees exceeds `max_total_cost`,
+    /// or if the trade is executed after `deadline`.
+    /// The subject may pass `settings` with their first share.
+    /// Shares are credited to `recipient` if given, otherwise to the sender.
+    /// A `referrer` earns a share of the protocol fee.
+    BuyShares {
+        subject: String,
+        amount: Uint128,
+        recipient: Option<String>,
+        referrer: Option<String>,
+        max_total_cost: Option<Uint128>,
+        deadline: Option<Expiration>,
+        settings: Option<SubjectSettings>,
+    },
+    /// Buy as many shares as the attached funds cover including fees, refunding
+    /// the remainder. Fails if that is fewer than `min_amount` shares.
+    BuySharesWithFunds {
+        subject: String,
+        min_amount: Uint128,
+    },
+    /// Fails if the proceeds after fees are below `min_proceeds`,
+    /// or if the trade is executed after `deadline`.
+    /// Proceeds go to `recipient` if given, otherwise to the sender. With `msg`
+    /// they are attached to a `ReceiveMsg::SellProceeds` callback on the recipient
+    /// contract, which can not be this contract.
+    /// A `referrer` earns a share of the protocol fee.
+    SellShares {
+        subject: String,
+        amount: Uint128,
+        recipient: Option<String>,
+        referrer: Option<String>,
+        min_proceeds: Option<Uint128>,
+        deadline: Option<Expiration>,
+        msg: Option<Binary>,
+    },
+    /// Sell every share of the sender's balance that can be sold.
+    SellAll {
+        subject: String,
+        min_proceeds: Option<Uint128>,
+    },
+    /// Execute several trades atomically, in order. Buys are paid from the attached
+    /// funds and the proceeds of the batch's sells, and the net remainder is sent
+    /// back in a single payout.
+    Batch { trades: Vec<Trade> },
+    /// Move shares to another address without trading them.
+    TransferShares {
+        subject: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Let `spender` transfer up to `amount` more of the sender's shares of `subject`.
+    IncreaseAllowance {
+        subject: String,
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// Lower the shares of `subject` that `spender` may transfer for the sender.
+    DecreaseAllowance {
+        subject: String,
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// Transfer shares of `owner` using an allowance granted to the sender.
+    TransferSharesFrom {
+        subject: String,
+        owner: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Allow or forbid transfers of a subject's shares. Only callable by the admin.
+    SetTransfersEnabled { subject: String, enabled: bool },
+    /// Only callable by the admin. Fields left as `None` are unchanged.
+    /// A new `curve` only applies to subjects activated afterwards.
+    UpdateConfig {
+        protocol_fee_bps: Option<u64>,
+        subject_fee_bps: Option<u64>,
+        referral_fee_bps: Option<u64>,
+        burn_bps: Option<u64>,
+        curve: Option<CurveConfig>,
+        subject_bounds: Option<SubjectBounds>,
+    },
+    /// Replace the protocol fee split. Weights must add up to 10_000 bps.
+    /// Only callable by the admin.
+    UpdateProtocolFeeRecipients {
+        recipients: Vec<ProtocolFeeRecipient>,
+    },
+    /// Rebuild the share indexes of a contract migrated from 0.1.0, visiting up
+    /// to `limit` balances per call. Trading and transfers are blocked until the
+    /// rebuild is done. Only callable by the admin.
+    MigrateIndexes { limit: Option<u32> },
+    /// Propose a new admin. The transfer only takes effect once the new admin
+    /// accepts it, and optionally lapses after `expiry`.
+    ProposeNewAdmin {
+        new_admin: String,
+        expiry: Option<Expiration>,
+    },
+    /// Accept a pending admin proposal. Must be sent by the proposed admin.
+    AcceptAdmin {},
+    /// Withdraw a pending admin proposal. Only callable by the current admin.
+    CancelAdminProposal {},
+    /// Halt trading for the whole contract, or only for `subject` if given.
+    /// With `allow_sells` holders can still exit their positions.
+    /// Only callable by the admin.
+    Pause {
+        subject: Option<String>,
+        allow_sells: bool,
+    },
+    /// Resume trading for the whole contract, or only for `subject` if given.
+    /// Only callable by the admin.
+    Unpause { subject: Option<String> },
+    /// Pay out the subject fees accrued to the sender.
+    ClaimFees {},
+    /// Pay out the referral fees accrued to the sender.
+    ClaimReferralFees {},
+    /// Pay out the protocol fees accrued to the sender as a protocol fee recipient.
+    WithdrawProtocolFees {},
+}
+
+/// Callback executed on the recipient contract of a `SellShares` with `msg`,
+/// with the proceeds attached.
+#[cw_serde]
+pub enum ReceiveMsg {
+    SellProceeds {
+        seller: String,
+        subject: String,
+        amount: Uint128,
+        msg: Binary,
+    },
 }
 
 #[cw_serde]
@@ -22,10 +173,64 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     #[returns(Config)]
     Config {},
+    #[returns(OwnershipResponse)]
+    Ownership {},
+    #[returns(PauseStatusResponse)]
+    PauseStatus { subject: Option<String> },
+    #[returns(PortfolioResponse)]
+    Portfolio {
+        holder: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Subjects that have bought their first share, ordered by address.
+    #[returns(SubjectsResponse)]
+    Subjects {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order_by: Option<OrderBy>,
+    },
+    #[returns(PendingFeesResponse)]
+    PendingFees { address: String },
+    #[returns(ReferralStatsResponse)]
+    ReferralStats { referrer: String },
+    #[returns(Option<SubjectConfig>)]
+    SubjectConfig { subject: String },
     #[returns(Uint128)]
     SharesBalance { subject: String, holder: String },
     #[returns(Uint128)]
     SharesSupply { subject: String },
+    #[returns(AllowanceResponse)]
+    Allowance {
+        subject: String,
+        owner: String,
+        spender: String,
+    },
+    #[returns(bool)]
+    TransfersEnabled { subject: String },
+    #[returns(HoldersResponse)]
+    Holders {
+        subject: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Full breakdown of buying `amount` shares, priced exactly as `BuyShares` would.
+    #[returns(TradeSimulation)]
+    SimulateBuy {
+        subject: String,
+        trader: String,
+        amount: Uint128,
+    },
+    /// Full breakdown of selling `amount` shares, priced exactly as `SellShares` would.
+    #[returns(TradeSimulation)]
+    SimulateSell {
+        subject: String,
+        trader: String,
+        amount: Uint128,
+    },
+    /// The most shares `funds` can buy including fees.
+    #[returns(Uint128)]
+    MaxBuyForFunds { subject: String, funds: Uint128 },
     #[returns(Coin)]
     BuyPrice { subject: String, amount: Uint128 },
     #[returns(Coin)]
@@ -36,45 +241,183 @@ pub enum QueryMsg {
     SellPriceAfterFee { subject: String, amount: Uint128 },
 }
 
+/// A single leg of a `Batch`.
+#[cw_serde]
+pub enum Trade {
+    Buy { subject: String, amount: Uint128 },
+    Sell { subject: String, amount: Uint128 },
+}
+
+/// Curve coefficient and fee a subject picks with their first share, within
+/// the admin's `SubjectBounds`.
+#[cw_serde]
+pub struct SubjectSettings {
+    pub curve_coefficient: Decimal,
+    pub subject_fee_bps: u64,
+}
+
+#[cw_serde]
+pub struct TradeSimulation {
+    /// Curve price before fees.
+    pub price: Uint128,
+    pub protocol_fee: Uint128,
+    pub subject_fee: Uint128,
+    /// Cost including fees for buys, proceeds after fees for sells.
+    pub total: Uint128,
+    /// Subject's supply after the trade.
+    pub supply: Uint128,
+    /// Trader's balance after the trade.
+    pub balance: Uint128,
+}
+
+#[cw_serde]
+pub struct Holder {
+    pub address: Addr,
+    pub balance: Uint128,
+}
+
+#[cw_serde]
+pub struct HoldersResponse {
+    pub holders: Vec<Holder>,
+}
+
+#[cw_serde]
+pub struct Position {
+    pub subject: Addr,
+    pub balance: Uint128,
+    /// What selling the sellable part of the balance would pay out after fees.
+    pub sell_value: Uint128,
+}
+
+#[cw_serde]
+pub struct PortfolioResponse {
+    pub positions: Vec<Position>,
+}
+
+#[cw_serde]
+pub enum OrderBy {
+    Ascending,
+    Descending,
+}
+
+#[cw_serde]
+pub struct SubjectSummary {
+    pub subject: Addr,
+    pub supply: Uint128,
+    pub holder_count: u64,
+    /// Block height of the first share, or 0 if unknown.
+    pub created_at: u64,
+}
+
+#[cw_serde]
+pub struct SubjectsResponse {
+    pub subjects: Vec<SubjectSummary>,
+}
+
+#[cw_serde]
+pub struct AllowanceResponse {
+    pub allowance: Uint128,
+    pub expires: Expiration,
+}
+
+#[cw_serde]
+pub struct PendingFeesResponse {
+    pub subject_fees: Uint128,
+    /// Accrued to the address as a protocol fee recipient, zero otherwise.
+    pub protocol_fees: Uint128,
+}
+
+#[cw_serde]
+pub struct ReferralStatsResponse {
+    pub trade_count: u64,
+    /// Curve price of the referred trades before fees.
+    pub volume: Uint128,
+    /// Referral fees earned over the referrer's lifetime.
+    pub earned: Uint128,
+    /// Referral fees not yet claimed.
+    pub pending: Uint128,
+}
+
+#[cw_serde]
+pub struct OwnershipResponse {
+    pub admin: Addr,
+    pub pending_admin: Option<Addr>,
+    pub pending_expiry: Option<Expiration>,
+}
+
+#[cw_serde]
+pub struct PauseStatusResponse {
+    pub global: TradingStatus,
+    pub subject: Option<TradingStatus>,
+}
+
 pub struct TradeEvent {
     pub trader: String,
+    /// Address the shares were credited to on buys, or the proceeds were paid to
+    /// on sells. Shares are always sold from `trader`.
+    pub recipient: String,
     pub subject: String,
     pub is_buy: bool,
     pub share_amount: Uint128,
     pub stars_amount: Uint128,
     pub protocol_stars_amount: Uint128,
     pub subject_stars_amount: Uint128,
+    pub refund_amount: Uint128,
     pub supply: u128,
+    pub referrer: Option<String>,
+    /// Part of `protocol_stars_amount` paid to the referrer.
+    pub referral_amount: Uint128,
+    /// Part of `protocol_stars_amount` that was burned.
+    pub burn_amount: Uint128,
 }
 impl TradeEvent {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         trader: impl Into<String>,
+        recipient: impl Into<String>,
         subject: impl Into<String>,
         is_buy: bool,
         share_amount: impl Into<Uint128>,
         stars_amount: Uint128,
         protocol_stars_amount: Uint128,
         subject_stars_amount: Uint128,
+        refund_amount: Uint128,
         supply: u128,
     ) -> Self {
         Self {
             trader: trader.into(),
+            recipient: recipient.into(),
             subject: subject.into(),
             is_buy,
             share_amount: share_amount.into(),
             stars_amount,
             protocol_stars_amount,
             subject_stars_amount,
+            refund_amount,
             supply,
+            referrer: None,
+            referral_amount: Uint128::zero(),
+            burn_amount: Uint128::zero(),
         }
     }
+
+    pub fn with_referral(mut self, referrer: Option<impl Into<String>>, amount: Uint128) -> Self {
+        self.referrer = referrer.map(Into::into);
+        self.referral_amount = amount;
+        self
+    }
+
+    pub fn with_burn(mut self, amount: Uint128) -> Self {
+        self.burn_amount = amount;
+        self
+    }
 }
 
 impl From<TradeEvent> for Event {
     fn from(val: TradeEvent) -> Self {
-        Event::new("Trade".to_string()).add_attributes(vec![
+        let event = Event::new("Trade".to_string()).add_attributes(vec![
             ("trader", val.trader),
+            ("recipient", val.recipient),
             ("subject", val.subject),
             ("is_buy", val.is_buy.to_string()),
             ("share_amount", val.share_amount.to_string()),
@@ -84,7 +427,38 @@ impl From<TradeEvent> for Event {
                 val.protocol_stars_amount.to_string(),
             ),
             ("subject_stars_amount", val.subject_stars_amount.to_string()),
+            ("refund_amount", val.refund_amount.to_string()),
             ("supply", val.supply.to_string()),
-        ])
+            ("referral_amount", val.referral_amount.to_string()),
+            ("burn_amount", val.burn_amount.to_string()),
+        ]);
+        match val.referrer {
+            Some(referrer) => event.add_attribute("referrer", referrer),
+            None => event,
+        }
+    }
+}
+
+pub struct TransferEvent {
+    pub subject: String,
+    pub owner: String,
+    pub recipient: String,
+    pub amount: Uint128,
+    /// Set when the transfer used an allowance.
+    pub spender: Option<String>,
+}
+
+impl From<TransferEvent> for Event {
+    fn from(val: TransferEvent) -> Self {
+        let event = Event::new("Transfer".to_string()).add_attributes(vec![
+            ("subject", val.subject),
+            ("owner", val.owner),
+            ("recipient", val.recipient),
+            ("amount", val.amount.to_string()),
+        ]);
+        match val.spender {
+            Some(spender) => event.add_attribute("spender", spender),
+            None => event,
+        }
     }
 }
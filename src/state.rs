@@ -1,23 +1,188 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal, StdResult, Storage, Uint128};
+use cosmwasm_std::{coin, Addr, Coin, Decimal, Empty, StdResult, Storage, Uint128};
 use cw_storage_plus::{Item, Map};
+use cw_utils::Expiration;
+
+use crate::curve::CurveConfig;
 
 #[cw_serde]
 pub struct Config {
-    pub protocol_fee_destination: Addr,
+    /// Denom that trades are paid in and fees are accrued in.
+    pub denom: String,
+    /// Addresses the protocol fee is split between, with weights summing to one.
+    pub protocol_fee_recipients: Vec<FeeRecipient>,
     pub protocol_fee_percent: Decimal,
     pub subject_fee_percent: Decimal,
-    pub curve_coefficient: Decimal,
+    /// Share of the protocol fee paid to the referrer of a trade.
+    pub referral_fee_percent: Decimal,
+    /// Share of the protocol fee left after referrals that is burned.
+    pub burn_percent: Decimal,
+    /// Curve for subjects activated from now on. Existing subjects keep the
+    /// curve they were activated with.
+    pub curve: CurveConfig,
+    /// Limits for the settings a subject may choose with their first share.
+    /// Subjects use the global settings when this is not set.
+    pub subject_bounds: Option<SubjectBounds>,
+}
+
+#[cw_serde]
+pub struct FeeRecipient {
+    pub address: Addr,
+    pub weight: Decimal,
+}
+
+#[cw_serde]
+pub struct SubjectBounds {
+    pub min_curve_coefficient: Decimal,
+    pub max_curve_coefficient: Decimal,
+    pub max_subject_fee_bps: u64,
+}
+
+impl Config {
+    /// `amount` of the payment denom.
+    pub fn coin(&self, amount: Uint128) -> Coin {
+        coin(amount.u128(), &self.denom)
+    }
+
+    /// Protocol and subject fees charged on a trade priced at `price`.
+    pub fn fees(&self, price: Uint128) -> (Uint128, Uint128) {
+        (
+            price * self.protocol_fee_percent,
+            price * self.subject_fee_percent,
+        )
+    }
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Settings fixed when a subject buys their first share.
+#[cw_serde]
+pub struct SubjectConfig {
+    /// Curve the subject's shares are priced on for as long as they exist.
+    pub curve: CurveConfig,
+    /// Fee chosen by the subject, or the global subject fee if not set.
+    pub subject_fee_percent: Option<Decimal>,
+}
+
+// (subject, config) for every subject activated since 0.2.0
+pub const SUBJECT_CONFIG: Map<Addr, SubjectConfig> = Map::new("sc");
+
+/// Curve of the subjects activated before 0.2.0, set when migrating from 0.1.0.
+pub const LEGACY_CURVE: Item<CurveConfig> = Item::new("legacy_curve");
+
+pub const ADMIN: Item<Addr> = Item::new("admin");
+
+/// An admin transfer that has been proposed but not yet accepted.
+#[cw_serde]
+pub struct PendingAdmin {
+    pub address: Addr,
+    pub expiry: Option<Expiration>,
+}
+
+pub const PENDING_ADMIN: Item<PendingAdmin> = Item::new("pending_admin");
+
+#[cw_serde]
+pub enum TradingStatus {
+    Active,
+    /// Only sells are allowed so holders can exit during an emergency.
+    SellsOnly,
+    Paused,
+}
+
+impl TradingStatus {
+    pub fn allows(&self, is_buy: bool) -> bool {
+        match self {
+            TradingStatus::Active => true,
+            TradingStatus::SellsOnly => !is_buy,
+            TradingStatus::Paused => false,
+        }
+    }
+}
+
+pub const TRADING_STATUS: Item<TradingStatus> = Item::new("trading_status");
+
+// (subject, status) only for subjects that are not active
+pub const SUBJECT_STATUS: Map<Addr, TradingStatus> = Map::new("sts");
+
 // ((subject, holder), balance)
 pub const SHARES_BALANCE: Map<(Addr, Addr), Uint128> = Map::new("sb");
 
+// ((holder, subject), _) reverse index of SHARES_BALANCE
+pub const HOLDER_SUBJECTS: Map<(Addr, Addr), Empty> = Map::new("hs");
+
 // (subject, supply)
 pub const SHARES_SUPPLY: Map<Addr, Uint128> = Map::new("ss");
 
+/// A subject that has bought their first share.
+#[cw_serde]
+#[derive(Default)]
+pub struct SubjectInfo {
+    /// Block height of the first share, or 0 if activated before subjects were tracked.
+    pub created_at: u64,
+    pub holder_count: u64,
+}
+
+// (subject, info) for every activated subject
+pub const SUBJECTS: Map<Addr, SubjectInfo> = Map::new("sj");
+
+/// Shares of a subject that a spender may transfer on behalf of the owner.
+#[cw_serde]
+pub struct Allowance {
+    pub amount: Uint128,
+    pub expires: Expiration,
+}
+
+// ((subject, owner, spender), allowance)
+pub const ALLOWANCES: Map<(Addr, Addr, Addr), Allowance> = Map::new("al");
+
+// (subject, _) only for subjects whose shares can not be transferred
+pub const TRANSFERS_DISABLED: Map<Addr, Empty> = Map::new("td");
+
+// (recipient, fees) accrued since the recipient's last withdrawal
+pub const PROTOCOL_FEES: Map<Addr, Uint128> = Map::new("pf");
+
+// (subject, fees) accrued since the subject's last claim
+pub const SUBJECT_FEES: Map<Addr, Uint128> = Map::new("sf");
+
+// (referrer, fees) accrued since the referrer's last claim
+pub const REFERRAL_FEES: Map<Addr, Uint128> = Map::new("rf");
+
+/// Lifetime totals of the trades a referrer brought in.
+#[cw_serde]
+#[derive(Default)]
+pub struct ReferralStats {
+    pub trade_count: u64,
+    /// Curve price of the referred trades before fees.
+    pub volume: Uint128,
+    pub earned: Uint128,
+}
+
+// (referrer, stats)
+pub const REFERRAL_STATS: Map<Addr, ReferralStats> = Map::new("rs");
+
+/// Loads the config with the subject's own settings applied. Subjects that are
+/// not activated yet use the global settings.
+pub fn load_subject_config(storage: &dyn Storage, subject: Addr) -> StdResult<Config> {
+    let mut config = CONFIG.load(storage)?;
+
+    match SUBJECT_CONFIG.may_load(storage, subject.clone())? {
+        Some(subject_config) => {
+            config.curve = subject_config.curve;
+            if let Some(subject_fee_percent) = subject_config.subject_fee_percent {
+                config.subject_fee_percent = subject_fee_percent;
+            }
+        }
+        None if SHARES_SUPPLY.has(storage, subject) => {
+            if let Some(curve) = LEGACY_CURVE.may_load(storage)? {
+                config.curve = curve;
+            }
+        }
+        None => {}
+    }
+
+    Ok(config)
+}
+
 pub fn load_supply(storage: &dyn Storage, subject: Addr) -> StdResult<u128> {
     Ok(SHARES_SUPPLY
         .may_load(storage, subject)?
@@ -25,6 +190,18 @@ pub fn load_supply(storage: &dyn Storage, subject: Addr) -> StdResult<u128> {
         .u128())
 }
 
+/// The part of the holder's balance that can be sold. The subject's last share
+/// can never be sold, so it is kept back from a holder owning the whole supply.
+pub fn sellable_balance(storage: &dyn Storage, subject: Addr, holder: Addr) -> StdResult<Uint128> {
+    let balance = SHARES_BALANCE
+        .may_load(storage, (subject.clone(), holder))?
+        .unwrap_or_default();
+    if !balance.is_zero() && balance.u128() == load_supply(storage, subject)? {
+        return Ok(balance - Uint128::one());
+    }
+    Ok(balance)
+}
+
 pub fn increment_shares(
     storage: &mut dyn Storage,
     subject: Addr,
@@ -33,14 +210,10 @@ pub fn increment_shares(
 ) -> StdResult<()> {
     let amount = amount.into();
 
-    SHARES_BALANCE.update(
-        storage,
-        (subject.clone(), sender),
-        |balance| -> StdResult<_> { Ok(balance.unwrap_or_default() + amount) },
-    )?;
+    credit_shares(storage, &subject, &sender, amount)?;
 
     SHARES_SUPPLY.update(storage, subject, |supply| -> StdResult<_> {
-        Ok(supply.unwrap_or_default() + amount)
+        Ok(supply.unwrap_or_default().checked_add(amount)?)
     })?;
 
     Ok(())
@@ -54,11 +227,7 @@ pub fn decrement_shares(
 ) -> StdResult<()> {
     let amount = amount.into();
 
-    SHARES_BALANCE.update(
-        storage,
-        (subject.clone(), sender),
-        |balance| -> StdResult<_> { Ok(balance.unwrap_or_default().checked_sub(amount)?) },
-    )?;
+    debit_shares(storage, &subject, &sender, amount)?;
 
     SHARES_SUPPLY.update(storage, subject, |supply| -> StdResult<_> {
         Ok(supply.unwrap_or_default().checked_sub(amount)?)
@@ -66,3 +235,142 @@ pub fn decrement_shares(
 
     Ok(())
 }
+
+/// Moves shares between holders without changing the supply.
+pub fn transfer_shares(
+    storage: &mut dyn Storage,
+    subject: &Addr,
+    from: &Addr,
+    to: &Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    debit_shares(storage, subject, from, amount)?;
+    credit_shares(storage, subject, to, amount)
+}
+
+fn credit_shares(
+    storage: &mut dyn Storage,
+    subject: &Addr,
+    holder: &Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    let key = (subject.clone(), holder.clone());
+    let balance = SHARES_BALANCE.may_load(storage, key.clone())?;
+    SHARES_BALANCE.save(
+        storage,
+        key,
+        &balance.unwrap_or_default().checked_add(amount)?,
+    )?;
+
+    if balance.is_none() {
+        HOLDER_SUBJECTS.save(storage, (holder.clone(), subject.clone()), &Empty {})?;
+        SUBJECTS.update(storage, subject.clone(), |info| -> StdResult<_> {
+            let mut info = info.unwrap_or_default();
+            info.holder_count += 1;
+            Ok(info)
+        })?;
+    }
+
+    Ok(())
+}
+
+fn debit_shares(
+    storage: &mut dyn Storage,
+    subject: &Addr,
+    holder: &Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    let key = (subject.clone(), holder.clone());
+    let balance = SHARES_BALANCE
+        .may_load(storage, key.clone())?
+        .unwrap_or_default()
+        .checked_sub(amount)?;
+
+    // drop empty balances so holder listings only contain holders
+    if balance.is_zero() {
+        SHARES_BALANCE.remove(storage, key);
+        HOLDER_SUBJECTS.remove(storage, (holder.clone(), subject.clone()));
+        SUBJECTS.update(storage, subject.clone(), |info| -> StdResult<_> {
+            let mut info = info.unwrap_or_default();
+            info.holder_count = info.holder_count.saturating_sub(1);
+            Ok(info)
+        })?;
+    } else {
+        SHARES_BALANCE.save(storage, key, &balance)?;
+    }
+
+    Ok(())
+}
+
+pub fn load_trading_status(storage: &dyn Storage) -> StdResult<TradingStatus> {
+    Ok(TRADING_STATUS
+        .may_load(storage)?
+        .unwrap_or(TradingStatus::Active))
+}
+
+pub fn load_subject_status(storage: &dyn Storage, subject: Addr) -> StdResult<TradingStatus> {
+    Ok(SUBJECT_STATUS
+        .may_load(storage, subject)?
+        .unwrap_or(TradingStatus::Active))
+}
+
+/// Accrues the subject fee to the subject and splits the protocol fee between
+/// the protocol fee recipients by weight.
+pub fn accrue_fees(
+    storage: &mut dyn Storage,
+    recipients: &[FeeRecipient],
+    subject: Addr,
+    protocol_fee: Uint128,
+    subject_fee: Uint128,
+) -> StdResult<()> {
+    if let Some((last, others)) = recipients.split_last() {
+        let mut remaining = protocol_fee;
+        for recipient in others {
+            let share = protocol_fee * recipient.weight;
+            remaining = remaining.checked_sub(share)?;
+            accrue_protocol_fee(storage, &recipient.address, share)?;
+        }
+        // the last recipient also gets whatever rounding left over
+        accrue_protocol_fee(storage, &last.address, remaining)?;
+    }
+
+    if !subject_fee.is_zero() {
+        SUBJECT_FEES.update(storage, subject, |fees| -> StdResult<_> {
+            Ok(fees.unwrap_or_default().checked_add(subject_fee)?)
+        })?;
+    }
+
+    Ok(())
+}
+
+fn accrue_protocol_fee(storage: &mut dyn Storage, recipient: &Addr, fee: Uint128) -> StdResult<()> {
+    if !fee.is_zero() {
+        PROTOCOL_FEES.update(storage, recipient.clone(), |fees| -> StdResult<_> {
+            Ok(fees.unwrap_or_default().checked_add(fee)?)
+        })?;
+    }
+    Ok(())
+}
+
+pub fn accrue_referral_fee(
+    storage: &mut dyn Storage,
+    referrer: Addr,
+    volume: Uint128,
+    fee: Uint128,
+) -> StdResult<()> {
+    REFERRAL_STATS.update(storage, referrer.clone(), |stats| -> StdResult<_> {
+        let mut stats = stats.unwrap_or_default();
+        stats.trade_count += 1;
+        stats.volume = stats.volume.checked_add(volume)?;
+        stats.earned = stats.earned.checked_add(fee)?;
+        Ok(stats)
+    })?;
+
+    if !fee.is_zero() {
+        REFERRAL_FEES.update(storage, referrer, |fees| -> StdResult<_> {
+            Ok(fees.unwrap_or_default().checked_add(fee)?)
+        })?;
+    }
+
+    Ok(())
+}
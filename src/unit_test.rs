@@ -1,30 +1,104 @@
-use crate::contract::{execute, instantiate, price, query};
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::Config;
+use crate::contract::{execute, instantiate, migrate, query, MAX_FEE_BPS};
+use crate::curve::{BondingCurve, CurveConfig, Exponential, Linear, Quadratic, Sigmoid};
+use crate::migrations::v0_2_0::{ConfigV0_1, CONFIG_V0_1};
+use crate::msg::{
+    AllowanceResponse, ExecuteMsg, Holder, HoldersResponse, InstantiateMsg, MigrateMsg, OrderBy,
+    OwnershipResponse, PauseStatusResponse, PendingFeesResponse, PortfolioResponse, Position,
+    ProtocolFeeRecipient, QueryMsg, ReceiveMsg, ReferralStatsResponse, SubjectSettings,
+    SubjectSummary, SubjectsResponse, Trade, TradeSimulation,
+};
+use crate::state::{
+    Config, SubjectBounds, SubjectConfig, SubjectInfo, TradingStatus, HOLDER_SUBJECTS,
+    SHARES_BALANCE, SHARES_SUPPLY, SUBJECTS,
+};
 
 use super::*;
-use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-use cosmwasm_std::{from_binary, BankMsg, Coin, CosmosMsg, Decimal, Uint128};
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    coins, from_binary, to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Deps, OwnedDeps,
+    Response, Uint128, WasmMsg,
+};
+use cw_utils::Expiration;
 use sg_std::stars;
 
 fn coefficient() -> Decimal {
     Decimal::from_ratio(1u128, 8u128)
 }
 
+fn curve() -> CurveConfig {
+    CurveConfig::Quadratic(Quadratic {
+        coefficient: coefficient(),
+    })
+}
+
+fn protocol_fee_recipients() -> Vec<ProtocolFeeRecipient> {
+    vec![ProtocolFeeRecipient {
+        address: "protocol_fee_destination".to_string(),
+        weight_bps: 10_000,
+    }]
+}
+
+fn instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        denom: None,
+        protocol_fee_recipients: protocol_fee_recipients(),
+        protocol_fee_bps: 500,
+        subject_fee_bps: 500,
+        referral_fee_bps: 0,
+        burn_bps: 0,
+        curve: curve(),
+        subject_bounds: None,
+    }
+}
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        instantiate_msg(),
+    )
+    .unwrap();
+    deps
+}
+
+fn buy_msg(subject: &str, amount: u128) -> ExecuteMsg {
+    ExecuteMsg::BuyShares {
+        subject: subject.to_string(),
+        amount: Uint128::from(amount),
+        recipient: None,
+        referrer: None,
+        max_total_cost: None,
+        deadline: None,
+        settings: None,
+    }
+}
+
 #[test]
-#[should_panic]
 fn invalid_price_arguments() {
     let supply = 0u128;
     let amount = 0u128;
-    let price = price(supply, amount, coefficient()).u128();
-    assert_eq!(price, 0u128);
+    let err = curve().price(supply, amount).unwrap_err();
+    assert_eq!(err, ContractError::InvalidAmount {});
+}
+
+#[test]
+fn price_overflow() {
+    let err = curve().price(u128::MAX, 1u128).unwrap_err();
+    assert_eq!(err, ContractError::Overflow {});
+
+    let err = curve().price(1u128 << 64, 1u128).unwrap_err();
+    assert_eq!(err, ContractError::Overflow {});
 }
 
 #[test]
 fn correct_price_for_first_share() {
     let supply = 0u128;
     let amount = 1u128;
-    let price = price(supply, amount, coefficient()).u128();
+    let price = curve().price(supply, amount).unwrap().u128();
     assert_eq!(price, 0);
 }
 
@@ -32,7 +106,7 @@ fn correct_price_for_first_share() {
 fn correct_price_for_second_share() {
     let supply = 1u128;
     let amount = 1u128;
-    let price = price(supply, amount, coefficient()).u128();
+    let price = curve().price(supply, amount).unwrap().u128();
     assert_eq!(price, 125_000);
 }
 
@@ -40,20 +114,51 @@ fn correct_price_for_second_share() {
 fn correct_price_for_third_share() {
     let supply = 2u128;
     let amount = 3u128;
-    let price = price(supply, amount, coefficient()).u128();
+    let price = curve().price(supply, amount).unwrap().u128();
     assert_eq!(price, 3_625_000);
 }
 
+#[test]
+fn correct_price_for_other_curve_families() {
+    let linear = CurveConfig::Linear(Linear {
+        coefficient: coefficient(),
+    });
+    // shares 1 and 2 cost 1 + 2 STARS before the coefficient
+    assert_eq!(linear.price(1, 2).unwrap().u128(), 375_000);
+
+    let exponential = CurveConfig::Exponential(Exponential {
+        coefficient: Decimal::one(),
+        growth_rate: Decimal::one(),
+    });
+    // (2^0 - 1) + (2^1 - 1) + (2^2 - 1)
+    assert_eq!(exponential.price(0, 3).unwrap().u128(), 4_000_000);
+    assert_eq!(exponential.price(3, 1).unwrap().u128(), 7_000_000);
+
+    let sigmoid = CurveConfig::Sigmoid(Sigmoid {
+        coefficient: Decimal::one(),
+        midpoint: Uint128::from(1u128),
+        smoothing: Decimal::zero(),
+    });
+    // flat at zero below the midpoint and at 2 STARS above it
+    assert_eq!(sigmoid.price(0, 1).unwrap().u128(), 0);
+    assert_eq!(sigmoid.price(0, 3).unwrap().u128(), 4_000_000);
+    assert_eq!(sigmoid.price(5, 1).unwrap().u128(), 2_000_000);
+
+    let invalid = CurveConfig::Exponential(Exponential {
+        coefficient: Decimal::one(),
+        growth_rate: Decimal::zero(),
+    });
+    assert!(matches!(
+        invalid.validate().unwrap_err(),
+        ContractError::InvalidCurve { .. }
+    ));
+}
+
 #[test]
 fn proper_initialization() {
     let mut deps = mock_dependencies();
 
-    let msg = InstantiateMsg {
-        protocol_fee_destination: "protocol_fee_destination".to_string(),
-        protocol_fee_bps: 500,
-        subject_fee_bps: 500,
-        curve_coefficient: coefficient(),
-    };
+    let msg = instantiate_msg();
     let info = mock_info("creator", &[]);
 
     let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -66,26 +171,13 @@ fn proper_initialization() {
 
 #[test]
 fn buy_and_sell_shares() {
-    let mut deps = mock_dependencies();
+    let mut deps = setup();
     let protocol_fee_destination = "protocol_fee_destination";
 
-    let msg = InstantiateMsg {
-        protocol_fee_destination: protocol_fee_destination.to_string(),
-        protocol_fee_bps: 500,
-        subject_fee_bps: 500,
-        curve_coefficient: coefficient(),
-    };
-
     let subject = "subject";
 
-    let info = mock_info(subject, &[]);
-    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-
     let info = mock_info("anyone", &stars(2u128));
-    let msg = ExecuteMsg::BuyShares {
-        subject: subject.to_string(),
-        amount: Uint128::from(1u128),
-    };
+    let msg = buy_msg(subject, 1);
     let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
     assert_eq!(
         err,
@@ -95,10 +187,7 @@ fn buy_and_sell_shares() {
     );
 
     let info = mock_info(subject, &stars(2u128));
-    let msg = ExecuteMsg::BuyShares {
-        subject: subject.to_string(),
-        amount: Uint128::from(1u128),
-    };
+    let msg = buy_msg(subject, 1);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     // the subject is the holder and should have 1 share
@@ -156,19 +245,21 @@ fn buy_and_sell_shares() {
     // buy the same subject's shares as another friend
     let friend = "friend";
     let info = mock_info(friend, &stars(52_937_500u128));
-    let msg = ExecuteMsg::BuyShares {
-        subject: subject.to_string(),
-        amount: Uint128::from(10u128),
-    };
+    let msg = buy_msg(subject, 10);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    assert_eq!(2, res.messages.len());
-    assert_eq!(
-        CosmosMsg::Bank(BankMsg::Send {
-            to_address: protocol_fee_destination.to_string(),
-            amount: stars(2_406_250u128)
-        }),
-        res.messages[0].msg
-    );
+    assert_eq!(0, res.messages.len());
+
+    // fees are accrued instead of sent
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PendingFees {
+            address: protocol_fee_destination.to_string(),
+        },
+    )
+    .unwrap();
+    let value: PendingFeesResponse = from_binary(&res).unwrap();
+    assert_eq!(value.protocol_fees.u128(), 2_406_250u128);
 
     // friend should now have a balance of subject's shares
     let res = query(
@@ -228,9 +319,14 @@ fn buy_and_sell_shares() {
     let msg = ExecuteMsg::SellShares {
         subject: subject.to_string(),
         amount: Uint128::from(10u128),
+        recipient: None,
+        referrer: None,
+        min_proceeds: None,
+        deadline: None,
+        msg: None,
     };
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    assert_eq!(3, res.messages.len());
+    assert_eq!(1, res.messages.len());
     // friend lost money on their trade due to fees
     assert_eq!(
         CosmosMsg::Bank(BankMsg::Send {
@@ -239,12 +335,22 @@ fn buy_and_sell_shares() {
         }),
         res.messages[0].msg
     );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PendingFees {
+            address: subject.to_string(),
+        },
+    )
+    .unwrap();
+    let value: PendingFeesResponse = from_binary(&res).unwrap();
     assert_eq!(
-        CosmosMsg::Bank(BankMsg::Send {
-            to_address: protocol_fee_destination.to_string(),
-            amount: stars(2_406_250u128)
-        }),
-        res.messages[1].msg
+        value,
+        PendingFeesResponse {
+            subject_fees: Uint128::from(4_812_500u128),
+            protocol_fees: Uint128::zero(),
+        }
     );
 
     // friend should now have reset their shares of subject
@@ -272,3 +378,1954 @@ fn buy_and_sell_shares() {
     let value: Uint128 = from_binary(&res).unwrap();
     assert_eq!(Uint128::from(1u128), value);
 }
+
+#[test]
+fn update_config() {
+    let mut deps = setup();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        protocol_fee_bps: Some(250),
+        subject_fee_bps: None,
+        referral_fee_bps: None,
+        burn_bps: None,
+        curve: None,
+        subject_bounds: None,
+    };
+
+    // only the admin can update the config
+    let info = mock_info("anyone", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let info = mock_info("admin", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let value: Config = from_binary(&res).unwrap();
+    assert_eq!(Decimal::bps(250), value.protocol_fee_percent);
+    assert_eq!(Decimal::bps(500), value.subject_fee_percent);
+
+    // fees are capped
+    let info = mock_info("admin", &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        protocol_fee_bps: None,
+        subject_fee_bps: Some(MAX_FEE_BPS + 1),
+        referral_fee_bps: None,
+        burn_bps: None,
+        curve: None,
+        subject_bounds: None,
+    };
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::FeeTooHigh {
+            bps: MAX_FEE_BPS + 1,
+            max: MAX_FEE_BPS
+        }
+    );
+}
+
+#[test]
+fn two_step_admin_transfer() {
+    let mut deps = setup();
+
+    // nothing to accept yet
+    let info = mock_info("new_admin", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::AcceptAdmin {}).unwrap_err();
+    assert_eq!(err, ContractError::NoPendingAdmin {});
+
+    // only the admin can propose
+    let expiry = Expiration::AtHeight(mock_env().block.height + 100);
+    let msg = ExecuteMsg::ProposeNewAdmin {
+        new_admin: "new_admin".to_string(),
+        expiry: Some(expiry),
+    };
+    let info = mock_info("anyone", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let info = mock_info("admin", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Ownership {}).unwrap();
+    let value: OwnershipResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        value,
+        OwnershipResponse {
+            admin: Addr::unchecked("admin"),
+            pending_admin: Some(Addr::unchecked("new_admin")),
+            pending_expiry: Some(expiry),
+        }
+    );
+
+    // only the proposed admin can accept
+    let info = mock_info("anyone", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::AcceptAdmin {}).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // the proposal lapses after the expiry
+    let mut env = mock_env();
+    env.block.height += 100;
+    let info = mock_info("new_admin", &[]);
+    let err = execute(deps.as_mut(), env, info, ExecuteMsg::AcceptAdmin {}).unwrap_err();
+    assert_eq!(err, ContractError::AdminProposalExpired {});
+
+    let info = mock_info("new_admin", &[]);
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::AcceptAdmin {}).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Ownership {}).unwrap();
+    let value: OwnershipResponse = from_binary(&res).unwrap();
+    assert_eq!(value.admin, Addr::unchecked("new_admin"));
+    assert_eq!(value.pending_admin, None);
+
+    // the new admin can propose and then cancel
+    let msg = ExecuteMsg::ProposeNewAdmin {
+        new_admin: "admin".to_string(),
+        expiry: None,
+    };
+    let info = mock_info("new_admin", &[]);
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::CancelAdminProposal {},
+    )
+    .unwrap();
+
+    let info = mock_info("admin", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::AcceptAdmin {}).unwrap_err();
+    assert_eq!(err, ContractError::NoPendingAdmin {});
+}
+
+#[test]
+fn migrate_from_v0_1_0() {
+    let mut deps = mock_dependencies();
+
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:fren-party", "0.1.0").unwrap();
+    CONFIG_V0_1
+        .save(
+            deps.as_mut().storage,
+            &ConfigV0_1 {
+                protocol_fee_destination: Addr::unchecked("protocol_fee_destination"),
+                protocol_fee_percent: Decimal::bps(500),
+                subject_fee_percent: Decimal::bps(500),
+                curve_coefficient: coefficient(),
+            },
+        )
+        .unwrap();
+
+    // 0.1.0 kept empty balances and had no holder index
+    let key = |subject: &str, holder: &str| (Addr::unchecked(subject), Addr::unchecked(holder));
+    let storage = deps.as_mut().storage;
+    SHARES_BALANCE
+        .save(storage, key("subject", "subject"), &Uint128::from(2u128))
+        .unwrap();
+    SHARES_BALANCE
+        .save(storage, key("subject", "friend"), &Uint128::zero())
+        .unwrap();
+    SHARES_SUPPLY
+        .save(storage, Addr::unchecked("subject"), &Uint128::from(2u128))
+        .unwrap();
+
+    // 0.1.0 had no admin, so one has to be provided
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg { admin: None }).unwrap_err();
+    assert_eq!(err, ContractError::MissingAdmin {});
+
+    let msg = MigrateMsg {
+        admin: Some("admin".to_string()),
+    };
+    migrate(deps.as_mut(), mock_env(), msg).unwrap();
+
+    let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let value: Config = from_binary(&res).unwrap();
+    assert_eq!(Decimal::bps(500), value.protocol_fee_percent);
+    assert_eq!(curve(), value.curve);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Ownership {}).unwrap();
+    let value: OwnershipResponse = from_binary(&res).unwrap();
+    assert_eq!(value.admin, Addr::unchecked("admin"));
+
+    // empty balances are not listed even before they are cleaned up
+    let msg = QueryMsg::Holders {
+        subject: "subject".to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let value: HoldersResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        value.holders,
+        vec![Holder {
+            address: Addr::unchecked("subject"),
+            balance: Uint128::from(2u128),
+        }]
+    );
+
+    // shares can not move until the indexes are rebuilt
+    let msg = ExecuteMsg::SellShares {
+        subject: "subject".to_string(),
+        amount: Uint128::one(),
+        recipient: None,
+        referrer: None,
+        min_proceeds: None,
+        deadline: None,
+        msg: None,
+    };
+    let info = mock_info("subject", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::MigrationPending {});
+
+    // only the admin rebuilds them, one page at a time
+    let rebuild = ExecuteMsg::MigrateIndexes { limit: Some(1) };
+    let info = mock_info("anyone", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, rebuild.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let done = |res: Response| {
+        res.attributes
+            .into_iter()
+            .find(|attribute| attribute.key == "done")
+            .unwrap()
+            .value
+    };
+    let info = mock_info("admin", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), rebuild.clone()).unwrap();
+    assert_eq!(done(res), "false");
+    assert!(!SHARES_BALANCE.has(deps.as_ref().storage, key("subject", "friend")));
+    assert!(!HOLDER_SUBJECTS.has(deps.as_ref().storage, key("subject", "subject")));
+
+    let rebuild = ExecuteMsg::MigrateIndexes { limit: None };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), rebuild.clone()).unwrap();
+    assert_eq!(done(res), "true");
+
+    // further calls are no-ops
+    let res = execute(deps.as_mut(), mock_env(), info, rebuild).unwrap();
+    assert_eq!(done(res), "true");
+
+    assert!(!SHARES_BALANCE.has(deps.as_ref().storage, key("subject", "friend")));
+    assert!(HOLDER_SUBJECTS.has(deps.as_ref().storage, key("subject", "subject")));
+    assert_eq!(
+        SUBJECTS
+            .load(deps.as_ref().storage, Addr::unchecked("subject"))
+            .unwrap(),
+        SubjectInfo {
+            created_at: 0,
+            holder_count: 1,
+        }
+    );
+
+    // subjects from 0.1.0 keep the quadratic curve after the global curve changes
+    let msg = ExecuteMsg::UpdateConfig {
+        protocol_fee_bps: None,
+        subject_fee_bps: None,
+        referral_fee_bps: None,
+        burn_bps: None,
+        curve: Some(CurveConfig::Linear(Linear {
+            coefficient: Decimal::one(),
+        })),
+        subject_bounds: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    let msg = QueryMsg::SellPrice {
+        subject: "subject".to_string(),
+        amount: Uint128::one(),
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let value: Coin = from_binary(&res).unwrap();
+    assert_eq!(value.amount.u128(), 125_000);
+}
+
+#[test]
+fn migrate_rejects_fees_above_cap() {
+    let mut deps = mock_dependencies();
+
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:fren-party", "0.1.0").unwrap();
+    CONFIG_V0_1
+        .save(
+            deps.as_mut().storage,
+            &ConfigV0_1 {
+                protocol_fee_destination: Addr::unchecked("protocol_fee_destination"),
+                protocol_fee_percent: Decimal::bps(500),
+                subject_fee_percent: Decimal::bps(MAX_FEE_BPS + 1),
+                curve_coefficient: coefficient(),
+            },
+        )
+        .unwrap();
+
+    let msg = MigrateMsg {
+        admin: Some("admin".to_string()),
+    };
+    let err = migrate(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::FeeTooHigh {
+            bps: MAX_FEE_BPS + 1,
+            max: MAX_FEE_BPS,
+        }
+    );
+}
+
+#[test]
+fn curve_update_only_applies_to_new_subjects() {
+    let mut deps = setup();
+
+    let info = mock_info("subject", &stars(1u128));
+    execute(deps.as_mut(), mock_env(), info, buy_msg("subject", 1)).unwrap();
+    let info = mock_info("friend", &stars(10_000_000u128));
+    execute(deps.as_mut(), mock_env(), info, buy_msg("subject", 1)).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        protocol_fee_bps: None,
+        subject_fee_bps: None,
+        referral_fee_bps: None,
+        burn_bps: None,
+        curve: Some(CurveConfig::Linear(Linear {
+            coefficient: Decimal::one(),
+        })),
+        subject_bounds: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    let price = |deps: Deps, msg: QueryMsg| {
+        let res = query(deps, mock_env(), msg).unwrap();
+        from_binary::<Coin>(&res).unwrap().amount.u128()
+    };
+
+    // the existing subject still sells share 2 on the quadratic curve it was bought on
+    let msg = QueryMsg::SellPrice {
+        subject: "subject".to_string(),
+        amount: Uint128::one(),
+    };
+    assert_eq!(price(deps.as_ref(), msg), 125_000);
+
+    // a subject activated after the update trades on the linear curve
+    let info = mock_info("newcomer", &stars(1u128));
+    execute(deps.as_mut(), mock_env(), info, buy_msg("newcomer", 1)).unwrap();
+    let msg = QueryMsg::BuyPrice {
+        subject: "newcomer".to_string(),
+        amount: Uint128::one(),
+    };
+    assert_eq!(price(deps.as_ref(), msg), 1_000_000);
+}
+
+#[test]
+fn migrate_rejects_downgrade_and_other_contracts() {
+    let mut deps = mock_dependencies();
+
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:fren-party", "99.0.0").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg { admin: None }).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CannotDowngrade {
+            stored: "99.0.0".to_string(),
+            new: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    );
+
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:other", "0.1.0").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg { admin: None }).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::WrongContract {
+            expected: "crates.io:fren-party".to_string(),
+            actual: "crates.io:other".to_string(),
+        }
+    );
+}
+
+#[test]
+fn slippage_protection() {
+    let mut deps = setup();
+    let subject = "subject";
+
+    let msg = buy_msg(subject, 1);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(subject, &stars(1u128)),
+        msg,
+    )
+    .unwrap();
+
+    // 10 shares cost 52_937_500 after fees
+    let friend = "friend";
+    let msg = ExecuteMsg::BuyShares {
+        subject: subject.to_string(),
+        amount: Uint128::from(10u128),
+        recipient: None,
+        referrer: None,
+        max_total_cost: Some(Uint128::from(52_937_499u128)),
+        deadline: None,
+        settings: None,
+    };
+    let info = mock_info(friend, &stars(60_000_000u128));
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SlippageExceeded {
+            expected: 52_937_499u128,
+            actual: 52_937_500u128,
+        }
+    );
+
+    let msg = ExecuteMsg::BuyShares {
+        subject: subject.to_string(),
+        amount: Uint128::from(10u128),
+        recipient: None,
+        referrer: None,
+        max_total_cost: Some(Uint128::from(52_937_500u128)),
+        deadline: None,
+        settings: None,
+    };
+    let info = mock_info(friend, &stars(52_937_500u128));
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // selling them back yields 43_312_500 after fees
+    let msg = ExecuteMsg::SellShares {
+        subject: subject.to_string(),
+        amount: Uint128::from(10u128),
+        recipient: None,
+        referrer: None,
+        min_proceeds: Some(Uint128::from(43_312_501u128)),
+        deadline: None,
+        msg: None,
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info(friend, &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SlippageExceeded {
+            expected: 43_312_501u128,
+            actual: 43_312_500u128,
+        }
+    );
+
+    let msg = ExecuteMsg::SellShares {
+        subject: subject.to_string(),
+        amount: Uint128::from(10u128),
+        recipient: None,
+        referrer: None,
+        min_proceeds: Some(Uint128::from(43_312_500u128)),
+        deadline: None,
+        msg: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(friend, &[]), msg).unwrap();
+}
+
+#[test]
+fn refund_overpayment() {
+    let mut deps = setup();
+    let subject = "subject";
+
+    let msg = buy_msg(subject, 1);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(subject, &stars(1u128)),
+        msg,
+    )
+    .unwrap();
+
+    // 10 shares cost 52_937_500 after fees, the friend attaches a buffer
+    let friend = "friend";
+    let msg = buy_msg(subject, 10);
+    let info = mock_info(friend, &stars(55_000_000u128));
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(1, res.messages.len());
+    assert_eq!(
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: friend.to_string(),
+            amount: stars(2_062_500u128)
+        }),
+        res.messages[0].msg
+    );
+
+    let event = &res.events[0];
+    let refund = event
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "refund_amount")
+        .unwrap();
+    assert_eq!(refund.value, "2062500");
+}
+
+#[test]
+fn trade_deadline() {
+    let mut deps = setup();
+    let subject = "subject";
+
+    let env = mock_env();
+    let deadline = Expiration::AtTime(env.block.time.plus_seconds(60));
+    let msg = ExecuteMsg::BuyShares {
+        subject: subject.to_string(),
+        amount: Uint128::from(1u128),
+        recipient: None,
+        referrer: None,
+        max_total_cost: None,
+        deadline: Some(deadline),
+        settings: None,
+    };
+
+    // the trade sat in the mempool past its deadline
+    let mut late_env = mock_env();
+    late_env.block.time = late_env.block.time.plus_seconds(60);
+    let info = mock_info(subject, &stars(1u128));
+    let err = execute(deps.as_mut(), late_env, info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::DeadlineExceeded {
+            deadline: deadline.to_string()
+        }
+    );
+
+    execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let deadline = Expiration::AtHeight(mock_env().block.height);
+    let msg = ExecuteMsg::SellShares {
+        subject: subject.to_string(),
+        amount: Uint128::from(1u128),
+        recipient: None,
+        referrer: None,
+        min_proceeds: None,
+        deadline: Some(deadline),
+        msg: None,
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info(subject, &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::DeadlineExceeded {
+            deadline: deadline.to_string()
+        }
+    );
+}
+
+#[test]
+fn pause_and_sells_only() {
+    let mut deps = setup();
+    let subject = "subject";
+    let sell = ExecuteMsg::SellShares {
+        subject: subject.to_string(),
+        amount: Uint128::from(1u128),
+        recipient: None,
+        referrer: None,
+        min_proceeds: None,
+        deadline: None,
+        msg: None,
+    };
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(subject, &stars(1u128)),
+        buy_msg(subject, 1),
+    )
+    .unwrap();
+    let info = mock_info("friend", &stars(1_000_000u128));
+    execute(deps.as_mut(), mock_env(), info, buy_msg(subject, 2)).unwrap();
+
+    // only the admin can pause
+    let msg = ExecuteMsg::Pause {
+        subject: None,
+        allow_sells: false,
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    let info = mock_info("friend", &stars(1_000_000u128));
+    let err = execute(deps.as_mut(), mock_env(), info, buy_msg(subject, 1)).unwrap_err();
+    assert_eq!(err, ContractError::Paused {});
+    let info = mock_info("friend", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, sell.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Paused {});
+
+    // sells-only mode for a single subject lets holders exit
+    let msg = ExecuteMsg::Unpause { subject: None };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    let msg = ExecuteMsg::Pause {
+        subject: Some(subject.to_string()),
+        allow_sells: true,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PauseStatus {
+            subject: Some(subject.to_string()),
+        },
+    )
+    .unwrap();
+    let value: PauseStatusResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        value,
+        PauseStatusResponse {
+            global: TradingStatus::Active,
+            subject: Some(TradingStatus::SellsOnly),
+        }
+    );
+
+    let info = mock_info("friend", &stars(1_000_000u128));
+    let err = execute(deps.as_mut(), mock_env(), info, buy_msg(subject, 1)).unwrap_err();
+    assert_eq!(err, ContractError::Paused {});
+    execute(deps.as_mut(), mock_env(), mock_info("friend", &[]), sell).unwrap();
+
+    let msg = ExecuteMsg::Unpause {
+        subject: Some(subject.to_string()),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    let info = mock_info("friend", &stars(1_000_000u128));
+    execute(deps.as_mut(), mock_env(), info, buy_msg(subject, 1)).unwrap();
+}
+
+#[test]
+fn price_queries_return_errors() {
+    let mut deps = setup();
+    let subject = "subject";
+
+    let msg = buy_msg(subject, 1);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(subject, &stars(1u128)),
+        msg,
+    )
+    .unwrap();
+
+    let msg = QueryMsg::SellPrice {
+        subject: subject.to_string(),
+        amount: Uint128::from(2u128),
+    };
+    let err = query(deps.as_ref(), mock_env(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InsufficientSupply {});
+
+    let msg = QueryMsg::BuyPriceAfterFee {
+        subject: subject.to_string(),
+        amount: Uint128::zero(),
+    };
+    let err = query(deps.as_ref(), mock_env(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidAmount {});
+
+    let msg = ExecuteMsg::BuyShares {
+        subject: subject.to_string(),
+        amount: Uint128::MAX,
+        recipient: None,
+        referrer: None,
+        max_total_cost: None,
+        deadline: None,
+        settings: None,
+    };
+    let info = mock_info("friend", &stars(1u128));
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::Overflow {});
+}
+
+#[test]
+fn subject_settings_at_first_buy() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        subject_bounds: Some(SubjectBounds {
+            min_curve_coefficient: Decimal::percent(10),
+            max_curve_coefficient: Decimal::one(),
+            max_subject_fee_bps: 800,
+        }),
+        ..instantiate_msg()
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    let subject = "subject";
+    let buy = |amount: u128, settings: Option<SubjectSettings>| ExecuteMsg::BuyShares {
+        subject: subject.to_string(),
+        amount: Uint128::from(amount),
+        recipient: None,
+        referrer: None,
+        max_total_cost: None,
+        deadline: None,
+        settings,
+    };
+
+    // settings must be within the admin's bounds
+    let settings = SubjectSettings {
+        curve_coefficient: Decimal::one(),
+        subject_fee_bps: 900,
+    };
+    let info = mock_info(subject, &stars(1u128));
+    let err = execute(deps.as_mut(), mock_env(), info, buy(1, Some(settings))).unwrap_err();
+    assert_eq!(err, ContractError::SubjectSettingsOutOfBounds {});
+
+    let settings = SubjectSettings {
+        curve_coefficient: Decimal::one(),
+        subject_fee_bps: 800,
+    };
+    let info = mock_info(subject, &stars(1u128));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        buy(1, Some(settings.clone())),
+    )
+    .unwrap();
+
+    // settings are locked in after the first share
+    let info = mock_info(subject, &stars(10_000_000u128));
+    let err = execute(deps.as_mut(), mock_env(), info, buy(1, Some(settings))).unwrap_err();
+    assert_eq!(err, ContractError::SubjectSettingsNotAllowed {});
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::SubjectConfig {
+            subject: subject.to_string(),
+        },
+    )
+    .unwrap();
+    let value: Option<SubjectConfig> = from_binary(&res).unwrap();
+    assert_eq!(
+        value,
+        Some(SubjectConfig {
+            curve: CurveConfig::Quadratic(Quadratic {
+                coefficient: Decimal::one(),
+            }),
+            subject_fee_percent: Some(Decimal::bps(800)),
+        })
+    );
+
+    // second share costs 1 STARS with a coefficient of 1, plus 5% + 8% fees
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::BuyPriceAfterFee {
+            subject: subject.to_string(),
+            amount: Uint128::from(1u128),
+        },
+    )
+    .unwrap();
+    let value: Coin = from_binary(&res).unwrap();
+    assert_eq!(value.amount.u128(), 1_130_000);
+
+    let info = mock_info("friend", &stars(1_130_000u128));
+    execute(deps.as_mut(), mock_env(), info, buy(1, None)).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PendingFees {
+            address: subject.to_string(),
+        },
+    )
+    .unwrap();
+    let value: PendingFeesResponse = from_binary(&res).unwrap();
+    assert_eq!(value.subject_fees.u128(), 80_000);
+}
+
+#[test]
+fn claim_accrued_fees() {
+    let mut deps = setup();
+    let subject = "subject";
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(subject, &stars(1u128)),
+        buy_msg(subject, 1),
+    )
+    .unwrap();
+
+    // the first share is free so nothing has accrued yet
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(subject, &[]),
+        ExecuteMsg::ClaimFees {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoFeesToClaim {});
+
+    let info = mock_info("friend", &stars(52_937_500u128));
+    execute(deps.as_mut(), mock_env(), info, buy_msg(subject, 10)).unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(subject, &[]),
+        ExecuteMsg::ClaimFees {},
+    )
+    .unwrap();
+    assert_eq!(
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: subject.to_string(),
+            amount: stars(2_406_250u128)
+        }),
+        res.messages[0].msg
+    );
+
+    // only the protocol fee destination can withdraw protocol fees
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::WithdrawProtocolFees {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("protocol_fee_destination", &[]),
+        ExecuteMsg::WithdrawProtocolFees {},
+    )
+    .unwrap();
+    assert_eq!(
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "protocol_fee_destination".to_string(),
+            amount: stars(2_406_250u128)
+        }),
+        res.messages[0].msg
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PendingFees {
+            address: "protocol_fee_destination".to_string(),
+        },
+    )
+    .unwrap();
+    let value: PendingFeesResponse = from_binary(&res).unwrap();
+    assert!(value.protocol_fees.is_zero());
+}
+
+#[test]
+fn paginate_holders() {
+    let mut deps = setup();
+    let subject = "subject";
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(subject, &stars(1u128)),
+        buy_msg(subject, 1),
+    )
+    .unwrap();
+    for holder in ["alice", "bob", "carol"] {
+        let info = mock_info(holder, &stars(100_000_000u128));
+        execute(deps.as_mut(), mock_env(), info, buy_msg(subject, 2)).unwrap();
+    }
+
+    // bob exits completely and is no longer listed
+    let msg = ExecuteMsg::SellShares {
+        subject: subject.to_string(),
+        amount: Uint128::from(2u128),
+        recipient: None,
+        referrer: None,
+        min_proceeds: None,
+        deadline: None,
+        msg: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), msg).unwrap();
+
+    let holders = |start_after: Option<&str>| -> Vec<Holder> {
+        let msg = QueryMsg::Holders {
+            subject: subject.to_string(),
+            start_after: start_after.map(String::from),
+            limit: Some(2),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        from_binary::<HoldersResponse>(&res).unwrap().holders
+    };
+
+    let page = holders(None);
+    assert_eq!(
+        page,
+        vec![
+            Holder {
+                address: Addr::unchecked("alice"),
+                balance: Uint128::from(2u128),
+            },
+            Holder {
+                address: Addr::unchecked("carol"),
+                balance: Uint128::from(2u128),
+            },
+        ]
+    );
+
+    let page = holders(Some("carol"));
+    assert_eq!(
+        page,
+        vec![Holder {
+            address: Addr::unchecked(subject),
+            balance: Uint128::from(1u128),
+        }]
+    );
+}
+
+#[test]
+fn portfolio_lists_subjects_held() {
+    let mut deps = setup();
+
+    for subject in ["alice", "bob", "carol"] {
+        let info = mock_info(subject, &stars(1u128));
+        execute(deps.as_mut(), mock_env(), info, buy_msg(subject, 1)).unwrap();
+        let info = mock_info("friend", &stars(100_000_000u128));
+        execute(deps.as_mut(), mock_env(), info, buy_msg(subject, 10)).unwrap();
+    }
+
+    // selling out of bob removes him from the portfolio
+    let msg = ExecuteMsg::SellShares {
+        subject: "bob".to_string(),
+        amount: Uint128::from(10u128),
+        recipient: None,
+        referrer: None,
+        min_proceeds: None,
+        deadline: None,
+        msg: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("friend", &[]), msg).unwrap();
+
+    let msg = QueryMsg::Portfolio {
+        holder: "friend".to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let value: PortfolioResponse = from_binary(&res).unwrap();
+    let position = |subject: &str| Position {
+        subject: Addr::unchecked(subject),
+        balance: Uint128::from(10u128),
+        sell_value: Uint128::from(43_312_500u128),
+    };
+    assert_eq!(value.positions, vec![position("alice"), position("carol")]);
+
+    let msg = QueryMsg::Portfolio {
+        holder: "friend".to_string(),
+        start_after: Some("alice".to_string()),
+        limit: Some(1),
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let value: PortfolioResponse = from_binary(&res).unwrap();
+    assert_eq!(value.positions, vec![position("carol")]);
+}
+
+#[test]
+fn portfolio_values_only_sellable_shares() {
+    let mut deps = mock_dependencies();
+
+    // the first share costs 2 STARS on this curve
+    let msg = InstantiateMsg {
+        curve: CurveConfig::Sigmoid(Sigmoid {
+            coefficient: Decimal::one(),
+            midpoint: Uint128::zero(),
+            smoothing: Decimal::zero(),
+        }),
+        ..instantiate_msg()
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    let msg = buy_msg("subject", 3);
+    let info = mock_info("subject", &stars(6_600_000u128));
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // shares 2 and 3 pay 4 STARS minus 10% fees, the first share stays
+    let msg = QueryMsg::Portfolio {
+        holder: "subject".to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let value: PortfolioResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        value.positions,
+        vec![Position {
+            subject: Addr::unchecked("subject"),
+            balance: Uint128::from(3u128),
+            sell_value: Uint128::from(3_600_000u128),
+        }]
+    );
+}
+
+#[test]
+fn list_subjects() {
+    let mut deps = setup();
+
+    let mut env = mock_env();
+    for subject in ["alice", "bob", "carol"] {
+        env.block.height += 1;
+        let info = mock_info(subject, &stars(1u128));
+        execute(deps.as_mut(), env.clone(), info, buy_msg(subject, 1)).unwrap();
+    }
+    for holder in ["dave", "erin"] {
+        let info = mock_info(holder, &stars(100_000_000u128));
+        execute(deps.as_mut(), env.clone(), info, buy_msg("bob", 2)).unwrap();
+    }
+
+    // selling out drops the holder from the count
+    let msg = ExecuteMsg::SellShares {
+        subject: "bob".to_string(),
+        amount: Uint128::from(2u128),
+        recipient: None,
+        referrer: None,
+        min_proceeds: None,
+        deadline: None,
+        msg: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("dave", &[]), msg).unwrap();
+
+    let msg = QueryMsg::Subjects {
+        start_after: None,
+        limit: None,
+        order_by: None,
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let value: SubjectsResponse = from_binary(&res).unwrap();
+    let height = mock_env().block.height;
+    assert_eq!(
+        value.subjects,
+        vec![
+            SubjectSummary {
+                subject: Addr::unchecked("alice"),
+                supply: Uint128::one(),
+                holder_count: 1,
+                created_at: height + 1,
+            },
+            SubjectSummary {
+                subject: Addr::unchecked("bob"),
+                supply: Uint128::from(3u128),
+                holder_count: 2,
+                created_at: height + 2,
+            },
+            SubjectSummary {
+                subject: Addr::unchecked("carol"),
+                supply: Uint128::one(),
+                holder_count: 1,
+                created_at: height + 3,
+            },
+        ]
+    );
+
+    let msg = QueryMsg::Subjects {
+        start_after: Some("carol".to_string()),
+        limit: Some(1),
+        order_by: Some(OrderBy::Descending),
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let value: SubjectsResponse = from_binary(&res).unwrap();
+    assert_eq!(value.subjects.len(), 1);
+    assert_eq!(value.subjects[0].subject, Addr::unchecked("bob"));
+}
+
+#[test]
+fn simulate_trades() {
+    let mut deps = setup();
+
+    // only the subject can buy the first share
+    let msg = QueryMsg::SimulateBuy {
+        subject: "subject".to_string(),
+        trader: "friend".to_string(),
+        amount: Uint128::one(),
+    };
+    let err = query(deps.as_ref(), mock_env(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NotSubject {
+            subject: "subject".to_string()
+        }
+    );
+
+    let msg = buy_msg("subject", 1);
+    let info = mock_info("subject", &stars(1u128));
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = QueryMsg::SimulateBuy {
+        subject: "subject".to_string(),
+        trader: "friend".to_string(),
+        amount: Uint128::from(10u128),
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let value: TradeSimulation = from_binary(&res).unwrap();
+    assert_eq!(
+        value,
+        TradeSimulation {
+            price: Uint128::from(48_125_000u128),
+            protocol_fee: Uint128::from(2_406_250u128),
+            subject_fee: Uint128::from(2_406_250u128),
+            total: Uint128::from(52_937_500u128),
+            supply: Uint128::from(11u128),
+            balance: Uint128::from(10u128),
+        }
+    );
+
+    // the quote is exactly what the trade costs
+    let msg = ExecuteMsg::BuyShares {
+        subject: "subject".to_string(),
+        amount: Uint128::from(10u128),
+        recipient: None,
+        referrer: None,
+        max_total_cost: Some(value.total),
+        deadline: None,
+        settings: None,
+    };
+    let info = mock_info("friend", &stars(value.total));
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert!(res.messages.is_empty());
+
+    let msg = QueryMsg::SimulateSell {
+        subject: "subject".to_string(),
+        trader: "friend".to_string(),
+        amount: Uint128::from(4u128),
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let value: TradeSimulation = from_binary(&res).unwrap();
+    assert_eq!(
+        value,
+        TradeSimulation {
+            price: Uint128::from(36_750_000u128),
+            protocol_fee: Uint128::from(1_837_500u128),
+            subject_fee: Uint128::from(1_837_500u128),
+            total: Uint128::from(33_075_000u128),
+            supply: Uint128::from(7u128),
+            balance: Uint128::from(6u128),
+        }
+    );
+
+    let msg = ExecuteMsg::SellShares {
+        subject: "subject".to_string(),
+        amount: Uint128::from(4u128),
+        recipient: None,
+        referrer: None,
+        min_proceeds: Some(value.total),
+        deadline: None,
+        msg: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("friend", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "friend".to_string(),
+            amount: stars(value.total),
+        })
+    );
+
+    // impossible sells fail the same way the trade would
+    let msg = QueryMsg::SimulateSell {
+        subject: "subject".to_string(),
+        trader: "friend".to_string(),
+        amount: Uint128::from(7u128),
+    };
+    let err = query(deps.as_ref(), mock_env(), msg).unwrap_err();
+    assert_eq!(err, ContractError::LastShare {});
+    let msg = QueryMsg::SimulateSell {
+        subject: "subject".to_string(),
+        trader: "subject".to_string(),
+        amount: Uint128::from(2u128),
+    };
+    let err = query(deps.as_ref(), mock_env(), msg).unwrap_err();
+    assert_eq!(err, ContractError::NotEnoughShares {});
+}
+
+#[test]
+fn buy_shares_with_funds() {
+    let mut deps = setup();
+
+    let msg = buy_msg("subject", 1);
+    let info = mock_info("subject", &stars(1u128));
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // 10 shares cost 52_937_500 with fees, 11 shares cost 69_575_000
+    let funds = 52_937_500u128 + 1_000_000;
+    let msg = QueryMsg::MaxBuyForFunds {
+        subject: "subject".to_string(),
+        funds: Uint128::from(funds),
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let value: Uint128 = from_binary(&res).unwrap();
+    assert_eq!(value, Uint128::from(10u128));
+
+    let msg = ExecuteMsg::BuySharesWithFunds {
+        subject: "subject".to_string(),
+        min_amount: Uint128::from(11u128),
+    };
+    let info = mock_info("friend", &stars(funds));
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SlippageExceeded {
+            expected: 11,
+            actual: 10,
+        }
+    );
+
+    let msg = ExecuteMsg::BuySharesWithFunds {
+        subject: "subject".to_string(),
+        min_amount: Uint128::from(10u128),
+    };
+    let info = mock_info("friend", &stars(funds));
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "friend".to_string(),
+            amount: stars(1_000_000u128),
+        })
+    );
+
+    let msg = QueryMsg::SharesBalance {
+        subject: "subject".to_string(),
+        holder: "friend".to_string(),
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let value: Uint128 = from_binary(&res).unwrap();
+    assert_eq!(value, Uint128::from(10u128));
+}
+
+#[test]
+fn batch_trades() {
+    let mut deps = setup();
+
+    for subject in ["alice", "bob"] {
+        let info = mock_info(subject, &stars(1u128));
+        execute(deps.as_mut(), mock_env(), info, buy_msg(subject, 1)).unwrap();
+    }
+    let info = mock_info("friend", &stars(52_937_500u128));
+    execute(deps.as_mut(), mock_env(), info, buy_msg("alice", 10)).unwrap();
+
+    let msg = ExecuteMsg::Batch { trades: vec![] };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("friend", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::EmptyBatch {});
+
+    // selling 4 alice pays 33_075_000 and buying 2 bob costs 687_500
+    let msg = ExecuteMsg::Batch {
+        trades: vec![
+            Trade::Sell {
+                subject: "alice".to_string(),
+                amount: Uint128::from(4u128),
+            },
+            Trade::Buy {
+                subject: "bob".to_string(),
+                amount: Uint128::from(2u128),
+            },
+        ],
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("friend", &[]), msg).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "friend".to_string(),
+            amount: stars(32_387_500u128),
+        })
+    );
+    assert_eq!(res.events.len(), 2);
+    assert!(res.events.iter().all(|event| event.ty == "Trade"));
+
+    let msg = QueryMsg::PendingFees {
+        address: "bob".to_string(),
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let value: PendingFeesResponse = from_binary(&res).unwrap();
+    assert_eq!(value.subject_fees, Uint128::from(31_250u128));
+
+    // the proceeds of the batch's sells do not cover its buys
+    let msg = ExecuteMsg::Batch {
+        trades: vec![
+            Trade::Sell {
+                subject: "bob".to_string(),
+                amount: Uint128::one(),
+            },
+            Trade::Buy {
+                subject: "alice".to_string(),
+                amount: Uint128::one(),
+            },
+        ],
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("friend", &[]), msg).unwrap_err();
+    assert!(matches!(err, ContractError::NotEnoughFunds { .. }));
+}
+
+#[test]
+fn transfer_shares_and_allowances() {
+    let mut deps = setup();
+
+    let msg = buy_msg("subject", 5);
+    let info = mock_info("subject", &stars(10_000_000u128));
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let balance = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, holder: &str| -> Uint128 {
+        let msg = QueryMsg::SharesBalance {
+            subject: "subject".to_string(),
+            holder: holder.to_string(),
+        };
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+    };
+
+    let msg = ExecuteMsg::TransferShares {
+        subject: "subject".to_string(),
+        recipient: "wallet".to_string(),
+        amount: Uint128::from(2u128),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("subject", &[]), msg).unwrap();
+    assert_eq!(res.events[0].ty, "Transfer");
+    assert_eq!(balance(&deps, "subject"), Uint128::from(3u128));
+    assert_eq!(balance(&deps, "wallet"), Uint128::from(2u128));
+
+    let msg = ExecuteMsg::TransferShares {
+        subject: "subject".to_string(),
+        recipient: "friend".to_string(),
+        amount: Uint128::from(3u128),
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("wallet", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::NotEnoughShares {});
+
+    // spending an allowance
+    let msg = ExecuteMsg::IncreaseAllowance {
+        subject: "subject".to_string(),
+        spender: "relayer".to_string(),
+        amount: Uint128::from(2u128),
+        expires: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("wallet", &[]), msg).unwrap();
+
+    let transfer_from = |amount: u128| ExecuteMsg::TransferSharesFrom {
+        subject: "subject".to_string(),
+        owner: "wallet".to_string(),
+        recipient: "friend".to_string(),
+        amount: Uint128::from(amount),
+    };
+    let info = mock_info("relayer", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), transfer_from(3)).unwrap_err();
+    assert_eq!(err, ContractError::InsufficientAllowance {});
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), transfer_from(1)).unwrap();
+    assert_eq!(res.events[0].attributes.last().unwrap().value, "relayer");
+    assert_eq!(balance(&deps, "friend"), Uint128::one());
+
+    let msg = QueryMsg::Allowance {
+        subject: "subject".to_string(),
+        owner: "wallet".to_string(),
+        spender: "relayer".to_string(),
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let value: AllowanceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        value,
+        AllowanceResponse {
+            allowance: Uint128::one(),
+            expires: Expiration::Never {},
+        }
+    );
+
+    // expired allowances can not be spent
+    let mut env = mock_env();
+    let msg = ExecuteMsg::IncreaseAllowance {
+        subject: "subject".to_string(),
+        spender: "relayer".to_string(),
+        amount: Uint128::zero(),
+        expires: Some(Expiration::AtHeight(env.block.height + 1)),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("wallet", &[]), msg).unwrap();
+    env.block.height += 1;
+    let err = execute(deps.as_mut(), env, info.clone(), transfer_from(1)).unwrap_err();
+    assert_eq!(err, ContractError::AllowanceExpired {});
+
+    // the admin can lock a market's shares in place
+    let msg = ExecuteMsg::SetTransfersEnabled {
+        subject: "subject".to_string(),
+        enabled: false,
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("wallet", &[]),
+        msg.clone(),
+    );
+    assert_eq!(err.unwrap_err(), ContractError::Unauthorized {});
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::TransferShares {
+        subject: "subject".to_string(),
+        recipient: "friend".to_string(),
+        amount: Uint128::one(),
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("wallet", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::TransfersDisabled {});
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::TransfersEnabled {
+            subject: "subject".to_string(),
+        },
+    )
+    .unwrap();
+    assert!(!from_binary::<bool>(&res).unwrap());
+}
+
+#[test]
+fn buy_shares_for_recipient() {
+    let mut deps = setup();
+
+    let gift = |amount: u128, recipient: &str| ExecuteMsg::BuyShares {
+        subject: "subject".to_string(),
+        amount: Uint128::from(amount),
+        recipient: Some(recipient.to_string()),
+        referrer: None,
+        max_total_cost: None,
+        deadline: None,
+        settings: None,
+    };
+
+    // only the subject can buy the first share, and only for themselves
+    let info = mock_info("payer", &stars(1u128));
+    let err = execute(deps.as_mut(), mock_env(), info, gift(1, "subject")).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NotSubject {
+            subject: "subject".to_string()
+        }
+    );
+    let info = mock_info("subject", &stars(1u128));
+    let err = execute(deps.as_mut(), mock_env(), info, gift(1, "friend")).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NotSubject {
+            subject: "subject".to_string()
+        }
+    );
+    let info = mock_info("subject", &stars(1u128));
+    execute(deps.as_mut(), mock_env(), info, gift(1, "subject")).unwrap();
+
+    // the payer gets the refund and the recipient gets the shares
+    let info = mock_info("payer", &stars(1_000_000u128));
+    let res = execute(deps.as_mut(), mock_env(), info, gift(1, "friend")).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "payer".to_string(),
+            amount: stars(862_500u128),
+        })
+    );
+    let attribute = |key: &str| {
+        res.events[0]
+            .attributes
+            .iter()
+            .find(|attribute| attribute.key == key)
+            .unwrap()
+            .value
+            .clone()
+    };
+    assert_eq!(attribute("trader"), "payer");
+    assert_eq!(attribute("recipient"), "friend");
+
+    for (holder, balance) in [("payer", 0u128), ("friend", 1)] {
+        let msg = QueryMsg::SharesBalance {
+            subject: "subject".to_string(),
+            holder: holder.to_string(),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: Uint128 = from_binary(&res).unwrap();
+        assert_eq!(value, Uint128::from(balance));
+    }
+}
+
+#[test]
+fn sell_shares_to_recipient() {
+    let mut deps = setup();
+
+    let info = mock_info("subject", &stars(1u128));
+    execute(deps.as_mut(), mock_env(), info, buy_msg("subject", 1)).unwrap();
+    let info = mock_info("friend", &stars(10_000_000u128));
+    execute(deps.as_mut(), mock_env(), info, buy_msg("subject", 2)).unwrap();
+
+    // share 2 pays 500_000 minus 10% fees
+    let sell = |recipient: &str, msg: Option<cosmwasm_std::Binary>| ExecuteMsg::SellShares {
+        subject: "subject".to_string(),
+        amount: Uint128::one(),
+        recipient: Some(recipient.to_string()),
+        referrer: None,
+        min_proceeds: None,
+        deadline: None,
+        msg,
+    };
+    let info = mock_info("friend", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), sell("cold", None)).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "cold".to_string(),
+            amount: stars(450_000u128),
+        })
+    );
+
+    // proceeds can not be routed back into this contract
+    let contract = mock_env().contract.address.to_string();
+    let msg = sell(&contract, Some(to_binary(&"deposit").unwrap()));
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidRecipient {});
+
+    // share 1 pays 125_000 minus 10% fees, wrapped in a typed callback
+    let callback = to_binary(&"deposit").unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        sell("vault", Some(callback.clone())),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "vault".to_string(),
+            msg: to_binary(&ReceiveMsg::SellProceeds {
+                seller: "friend".to_string(),
+                subject: "subject".to_string(),
+                amount: Uint128::from(112_500u128),
+                msg: callback,
+            })
+            .unwrap(),
+            funds: stars(112_500u128),
+        })
+    );
+}
+
+#[test]
+fn sell_all_shares() {
+    let mut deps = setup();
+
+    let info = mock_info("subject", &stars(10_000_000u128));
+    execute(deps.as_mut(), mock_env(), info, buy_msg("subject", 2)).unwrap();
+    let info = mock_info("friend", &stars(10_000_000u128));
+    execute(deps.as_mut(), mock_env(), info, buy_msg("subject", 2)).unwrap();
+
+    let sell_all = ExecuteMsg::SellAll {
+        subject: "subject".to_string(),
+        min_proceeds: None,
+    };
+    let balance = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, holder: &str| -> Uint128 {
+        let msg = QueryMsg::SharesBalance {
+            subject: "subject".to_string(),
+            holder: holder.to_string(),
+        };
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+    };
+
+    let info = mock_info("friend", &[]);
+    execute(deps.as_mut(), mock_env(), info.clone(), sell_all.clone()).unwrap();
+    assert_eq!(balance(&deps, "friend"), Uint128::zero());
+
+    let err = execute(deps.as_mut(), mock_env(), info, sell_all.clone()).unwrap_err();
+    assert_eq!(err, ContractError::NotEnoughShares {});
+
+    // the subject holds the whole supply, so the last share stays
+    let info = mock_info("subject", &[]);
+    execute(deps.as_mut(), mock_env(), info.clone(), sell_all.clone()).unwrap();
+    assert_eq!(balance(&deps, "subject"), Uint128::one());
+
+    let err = execute(deps.as_mut(), mock_env(), info, sell_all).unwrap_err();
+    assert_eq!(err, ContractError::LastShare {});
+}
+
+#[test]
+fn trade_in_configured_denom() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        denom: Some(" ".to_string()),
+        ..instantiate_msg()
+    };
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidDenom {});
+
+    let msg = InstantiateMsg {
+        denom: Some("uusdc".to_string()),
+        ..instantiate_msg()
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    let info = mock_info("subject", &stars(1u128));
+    let err = execute(deps.as_mut(), mock_env(), info, buy_msg("subject", 1)).unwrap_err();
+    assert!(matches!(err, ContractError::Payment(_)));
+
+    let info = mock_info("subject", &coins(1_000_000, "uusdc"));
+    let res = execute(deps.as_mut(), mock_env(), info, buy_msg("subject", 2)).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "subject".to_string(),
+            amount: coins(862_500, "uusdc"),
+        })
+    );
+
+    let msg = QueryMsg::SellPriceAfterFee {
+        subject: "subject".to_string(),
+        amount: Uint128::one(),
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let value: Coin = from_binary(&res).unwrap();
+    assert_eq!(value, Coin::new(112_500, "uusdc"));
+}
+
+#[test]
+fn referral_fees() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        referral_fee_bps: 10_001,
+        ..instantiate_msg()
+    };
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::FeeTooHigh {
+            bps: 10_001,
+            max: 10_000
+        }
+    );
+
+    // referrers earn 20% of the protocol fee
+    let msg = InstantiateMsg {
+        referral_fee_bps: 2_000,
+        ..instantiate_msg()
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    let buy = |amount: u128, referrer: Option<&str>| ExecuteMsg::BuyShares {
+        subject: "subject".to_string(),
+        amount: Uint128::from(amount),
+        recipient: None,
+        referrer: referrer.map(str::to_string),
+        max_total_cost: None,
+        deadline: None,
+        settings: None,
+    };
+    let info = mock_info("subject", &stars(1u128));
+    execute(deps.as_mut(), mock_env(), info, buy(1, None)).unwrap();
+
+    let info = mock_info("friend", &stars(52_937_500u128));
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        buy(10, Some("friend")),
+    );
+    assert_eq!(err.unwrap_err(), ContractError::SelfReferral {});
+
+    // nor can the shares go to the referrer
+    let msg = ExecuteMsg::BuyShares {
+        subject: "subject".to_string(),
+        amount: Uint128::from(10u128),
+        recipient: Some("cold".to_string()),
+        referrer: Some("cold".to_string()),
+        max_total_cost: None,
+        deadline: None,
+        settings: None,
+    };
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::SelfReferral {});
+
+    // the trader pays the same, the protocol fee of 2_406_250 is split with the referrer
+    let res = execute(deps.as_mut(), mock_env(), info, buy(10, Some("promoter"))).unwrap();
+    assert!(res.messages.is_empty());
+    let attribute = |key: &str| {
+        res.events[0]
+            .attributes
+            .iter()
+            .find(|attribute| attribute.key == key)
+            .unwrap()
+            .value
+            .clone()
+    };
+    assert_eq!(attribute("referrer"), "promoter");
+    assert_eq!(attribute("referral_amount"), "481250");
+
+    let msg = QueryMsg::PendingFees {
+        address: "protocol_fee_destination".to_string(),
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let value: PendingFeesResponse = from_binary(&res).unwrap();
+    assert_eq!(value.protocol_fees, Uint128::from(1_925_000u128));
+
+    let msg = QueryMsg::ReferralStats {
+        referrer: "promoter".to_string(),
+    };
+    let res = query(deps.as_ref(), mock_env(), msg.clone()).unwrap();
+    let value: ReferralStatsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        value,
+        ReferralStatsResponse {
+            trade_count: 1,
+            volume: Uint128::from(48_125_000u128),
+            earned: Uint128::from(481_250u128),
+            pending: Uint128::from(481_250u128),
+        }
+    );
+
+    let info = mock_info("promoter", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::ClaimReferralFees {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "promoter".to_string(),
+            amount: stars(481_250u128),
+        })
+    );
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::ClaimReferralFees {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoFeesToClaim {});
+
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let value: ReferralStatsResponse = from_binary(&res).unwrap();
+    assert_eq!(value.earned, Uint128::from(481_250u128));
+    assert_eq!(value.pending, Uint128::zero());
+}
+
+#[test]
+fn burn_protocol_fee() {
+    let mut deps = mock_dependencies();
+
+    // half of the protocol fee left after referrals is burned
+    let msg = InstantiateMsg {
+        referral_fee_bps: 2_000,
+        burn_bps: 5_000,
+        ..instantiate_msg()
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    let buy = |amount: u128, referrer: Option<&str>| ExecuteMsg::BuyShares {
+        subject: "subject".to_string(),
+        amount: Uint128::from(amount),
+        recipient: None,
+        referrer: referrer.map(str::to_string),
+        max_total_cost: None,
+        deadline: None,
+        settings: None,
+    };
+    let info = mock_info("subject", &stars(1u128));
+    execute(deps.as_mut(), mock_env(), info, buy(1, None)).unwrap();
+
+    // protocol fee of 2_406_250 minus 481_250 for the referrer, half of the rest burned
+    let info = mock_info("friend", &stars(52_937_500u128));
+    let res = execute(deps.as_mut(), mock_env(), info, buy(10, Some("promoter"))).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Burn {
+            amount: stars(962_500u128),
+        })
+    );
+    let burn_amount = res.events[0]
+        .attributes
+        .iter()
+        .find(|attribute| attribute.key == "burn_amount")
+        .unwrap();
+    assert_eq!(burn_amount.value, "962500");
+
+    let msg = QueryMsg::PendingFees {
+        address: "protocol_fee_destination".to_string(),
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let value: PendingFeesResponse = from_binary(&res).unwrap();
+    assert_eq!(value.protocol_fees, Uint128::from(962_500u128));
+
+    // without a referrer half of the whole protocol fee of 1_837_500 is burned
+    let msg = ExecuteMsg::SellShares {
+        subject: "subject".to_string(),
+        amount: Uint128::from(4u128),
+        recipient: None,
+        referrer: None,
+        min_proceeds: None,
+        deadline: None,
+        msg: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("friend", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Burn {
+            amount: stars(918_750u128),
+        })
+    );
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "friend".to_string(),
+            amount: stars(33_075_000u128),
+        })
+    );
+}
+
+#[test]
+fn split_protocol_fee() {
+    let mut deps = mock_dependencies();
+
+    let recipient = |address: &str, weight_bps: u64| ProtocolFeeRecipient {
+        address: address.to_string(),
+        weight_bps,
+    };
+    let msg = InstantiateMsg {
+        protocol_fee_recipients: vec![
+            recipient("treasury", 5_000),
+            recipient("dev_fund", 3_000),
+            recipient("community_pool", 2_000),
+        ],
+        ..instantiate_msg()
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    let info = mock_info("subject", &stars(1u128));
+    execute(deps.as_mut(), mock_env(), info, buy_msg("subject", 1)).unwrap();
+
+    // protocol fee of 2_406_250 split 50/30/20
+    let info = mock_info("friend", &stars(52_937_500u128));
+    execute(deps.as_mut(), mock_env(), info, buy_msg("subject", 10)).unwrap();
+
+    let protocol_fees = |deps: Deps, address: &str| {
+        let msg = QueryMsg::PendingFees {
+            address: address.to_string(),
+        };
+        let res = query(deps, mock_env(), msg).unwrap();
+        from_binary::<PendingFeesResponse>(&res)
+            .unwrap()
+            .protocol_fees
+    };
+    assert_eq!(
+        protocol_fees(deps.as_ref(), "treasury"),
+        Uint128::from(1_203_125u128)
+    );
+    assert_eq!(
+        protocol_fees(deps.as_ref(), "dev_fund"),
+        Uint128::from(721_875u128)
+    );
+    assert_eq!(
+        protocol_fees(deps.as_ref(), "community_pool"),
+        Uint128::from(481_250u128)
+    );
+
+    // each recipient withdraws only its own share
+    let msg = ExecuteMsg::WithdrawProtocolFees {};
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("dev_fund", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "dev_fund".to_string(),
+            amount: stars(721_875u128),
+        })
+    );
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("dev_fund", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoFeesToClaim {});
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("stranger", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // only the admin can change the split, and weights must add up to 100%
+    let update = |recipients| ExecuteMsg::UpdateProtocolFeeRecipients { recipients };
+    let info = mock_info("treasury", &[]);
+    let msg = update(vec![recipient("treasury", 10_000)]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    for (recipients, reason) in [
+        (vec![], "at least one is required"),
+        (
+            vec![recipient("treasury", 10_000), recipient("dev_fund", 0)],
+            "weights must be positive",
+        ),
+        (
+            vec![recipient("treasury", 5_000), recipient("treasury", 5_000)],
+            "duplicate recipient",
+        ),
+        (
+            vec![recipient("treasury", 5_000), recipient("dev_fund", 4_000)],
+            "weights must add up to 10000 bps",
+        ),
+    ] {
+        let info = mock_info("admin", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, update(recipients)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidFeeRecipients {
+                reason: reason.to_string()
+            }
+        );
+    }
+
+    let msg = update(vec![
+        recipient("treasury", 3_333),
+        recipient("dev_fund", 3_333),
+        recipient("new_fund", 3_334),
+    ]);
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    // protocol fee of 1_837_500 on the sell, rounding dust goes to the last recipient
+    let msg = ExecuteMsg::SellShares {
+        subject: "subject".to_string(),
+        amount: Uint128::from(4u128),
+        recipient: None,
+        referrer: None,
+        min_proceeds: None,
+        deadline: None,
+        msg: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("friend", &[]), msg).unwrap();
+    assert_eq!(
+        protocol_fees(deps.as_ref(), "treasury"),
+        Uint128::from(1_815_563u128)
+    );
+    assert_eq!(
+        protocol_fees(deps.as_ref(), "dev_fund"),
+        Uint128::from(612_438u128)
+    );
+    assert_eq!(
+        protocol_fees(deps.as_ref(), "new_fund"),
+        Uint128::from(612_624u128)
+    );
+
+    // a recipient dropped from the split can still withdraw what it accrued
+    let msg = ExecuteMsg::WithdrawProtocolFees {};
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("community_pool", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "community_pool".to_string(),
+            amount: stars(481_250u128),
+        })
+    );
+}
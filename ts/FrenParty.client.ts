@@ -6,10 +6,49 @@
 
 import { CosmWasmClient, SigningCosmWasmClient, ExecuteResult } from "@cosmjs/cosmwasm-stargate";
 import { StdFee } from "@cosmjs/amino";
-import { Decimal, InstantiateMsg, ExecuteMsg, Uint128, QueryMsg, Coin, Addr, Config } from "./FrenParty.types";
+import { CurveConfig, Decimal, Uint128, InstantiateMsg, Quadratic, Linear, Exponential, Sigmoid, ProtocolFeeRecipient, SubjectBounds, ExecuteMsg, Expiration, Timestamp, Uint64, Binary, Trade, SubjectSettings, QueryMsg, OrderBy, MigrateMsg, AllowanceResponse, Coin, Addr, Config, FeeRecipient, HoldersResponse, Holder, OwnershipResponse, TradingStatus, PauseStatusResponse, PendingFeesResponse, PortfolioResponse, Position, ReferralStatsResponse, TradeSimulation, NullableSubjectConfig, SubjectConfig, SubjectsResponse, SubjectSummary, Boolean } from "./FrenParty.types";
 export interface FrenPartyReadOnlyInterface {
   contractAddress: string;
   config: () => Promise<Config>;
+  ownership: () => Promise<OwnershipResponse>;
+  pauseStatus: ({
+    subject
+  }: {
+    subject?: string;
+  }) => Promise<PauseStatusResponse>;
+  portfolio: ({
+    holder,
+    limit,
+    startAfter
+  }: {
+    holder: string;
+    limit?: number;
+    startAfter?: string;
+  }) => Promise<PortfolioResponse>;
+  subjects: ({
+    limit,
+    orderBy,
+    startAfter
+  }: {
+    limit?: number;
+    orderBy?: OrderBy;
+    startAfter?: string;
+  }) => Promise<SubjectsResponse>;
+  pendingFees: ({
+    address
+  }: {
+    address: string;
+  }) => Promise<PendingFeesResponse>;
+  referralStats: ({
+    referrer
+  }: {
+    referrer: string;
+  }) => Promise<ReferralStatsResponse>;
+  subjectConfig: ({
+    subject
+  }: {
+    subject: string;
+  }) => Promise<NullableSubjectConfig>;
   sharesBalance: ({
     holder,
     subject
@@ -22,6 +61,54 @@ export interface FrenPartyReadOnlyInterface {
   }: {
     subject: string;
   }) => Promise<Uint128>;
+  allowance: ({
+    owner,
+    spender,
+    subject
+  }: {
+    owner: string;
+    spender: string;
+    subject: string;
+  }) => Promise<AllowanceResponse>;
+  transfersEnabled: ({
+    subject
+  }: {
+    subject: string;
+  }) => Promise<Boolean>;
+  holders: ({
+    limit,
+    startAfter,
+    subject
+  }: {
+    limit?: number;
+    startAfter?: string;
+    subject: string;
+  }) => Promise<HoldersResponse>;
+  simulateBuy: ({
+    amount,
+    subject,
+    trader
+  }: {
+    amount: Uint128;
+    subject: string;
+    trader: string;
+  }) => Promise<TradeSimulation>;
+  simulateSell: ({
+    amount,
+    subject,
+    trader
+  }: {
+    amount: Uint128;
+    subject: string;
+    trader: string;
+  }) => Promise<TradeSimulation>;
+  maxBuyForFunds: ({
+    funds,
+    subject
+  }: {
+    funds: Uint128;
+    subject: string;
+  }) => Promise<Uint128>;
   buyPrice: ({
     amount,
     subject
@@ -59,8 +146,21 @@ export class FrenPartyQueryClient implements FrenPartyReadOnlyInterface {
     this.client = client;
     this.contractAddress = contractAddress;
     this.config = this.config.bind(this);
+    this.ownership = this.ownership.bind(this);
+    this.pauseStatus = this.pauseStatus.bind(this);
+    this.portfolio = this.portfolio.bind(this);
+    this.subjects = this.subjects.bind(this);
+    this.pendingFees = this.pendingFees.bind(this);
+    this.referralStats = this.referralStats.bind(this);
+    this.subjectConfig = this.subjectConfig.bind(this);
     this.sharesBalance = this.sharesBalance.bind(this);
     this.sharesSupply = this.sharesSupply.bind(this);
+    this.allowance = this.allowance.bind(this);
+    this.transfersEnabled = this.transfersEnabled.bind(this);
+    this.holders = this.holders.bind(this);
+    this.simulateBuy = this.simulateBuy.bind(this);
+    this.simulateSell = this.simulateSell.bind(this);
+    this.maxBuyForFunds = this.maxBuyForFunds.bind(this);
     this.buyPrice = this.buyPrice.bind(this);
     this.sellPrice = this.sellPrice.bind(this);
     this.buyPriceAfterFee = this.buyPriceAfterFee.bind(this);
@@ -72,6 +172,89 @@ export class FrenPartyQueryClient implements FrenPartyReadOnlyInterface {
       config: {}
     });
   };
+  ownership = async (): Promise<OwnershipResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      ownership: {}
+    });
+  };
+  pauseStatus = async ({
+    subject
+  }: {
+    subject?: string;
+  }): Promise<PauseStatusResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      pause_status: {
+        subject
+      }
+    });
+  };
+  portfolio = async ({
+    holder,
+    limit,
+    startAfter
+  }: {
+    holder: string;
+    limit?: number;
+    startAfter?: string;
+  }): Promise<PortfolioResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      portfolio: {
+        holder,
+        limit,
+        start_after: startAfter
+      }
+    });
+  };
+  subjects = async ({
+    limit,
+    orderBy,
+    startAfter
+  }: {
+    limit?: number;
+    orderBy?: OrderBy;
+    startAfter?: string;
+  }): Promise<SubjectsResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      subjects: {
+        limit,
+        order_by: orderBy,
+        start_after: startAfter
+      }
+    });
+  };
+  pendingFees = async ({
+    address
+  }: {
+    address: string;
+  }): Promise<PendingFeesResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      pending_fees: {
+        address
+      }
+    });
+  };
+  referralStats = async ({
+    referrer
+  }: {
+    referrer: string;
+  }): Promise<ReferralStatsResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      referral_stats: {
+        referrer
+      }
+    });
+  };
+  subjectConfig = async ({
+    subject
+  }: {
+    subject: string;
+  }): Promise<NullableSubjectConfig> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      subject_config: {
+        subject
+      }
+    });
+  };
   sharesBalance = async ({
     holder,
     subject
@@ -97,6 +280,99 @@ export class FrenPartyQueryClient implements FrenPartyReadOnlyInterface {
       }
     });
   };
+  allowance = async ({
+    owner,
+    spender,
+    subject
+  }: {
+    owner: string;
+    spender: string;
+    subject: string;
+  }): Promise<AllowanceResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      allowance: {
+        owner,
+        spender,
+        subject
+      }
+    });
+  };
+  transfersEnabled = async ({
+    subject
+  }: {
+    subject: string;
+  }): Promise<Boolean> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      transfers_enabled: {
+        subject
+      }
+    });
+  };
+  holders = async ({
+    limit,
+    startAfter,
+    subject
+  }: {
+    limit?: number;
+    startAfter?: string;
+    subject: string;
+  }): Promise<HoldersResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      holders: {
+        limit,
+        start_after: startAfter,
+        subject
+      }
+    });
+  };
+  simulateBuy = async ({
+    amount,
+    subject,
+    trader
+  }: {
+    amount: Uint128;
+    subject: string;
+    trader: string;
+  }): Promise<TradeSimulation> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      simulate_buy: {
+        amount,
+        subject,
+        trader
+      }
+    });
+  };
+  simulateSell = async ({
+    amount,
+    subject,
+    trader
+  }: {
+    amount: Uint128;
+    subject: string;
+    trader: string;
+  }): Promise<TradeSimulation> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      simulate_sell: {
+        amount,
+        subject,
+        trader
+      }
+    });
+  };
+  maxBuyForFunds = async ({
+    funds,
+    subject
+  }: {
+    funds: Uint128;
+    subject: string;
+  }): Promise<Uint128> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      max_buy_for_funds: {
+        funds,
+        subject
+      }
+    });
+  };
   buyPrice = async ({
     amount,
     subject
@@ -159,18 +435,155 @@ export interface FrenPartyInterface extends FrenPartyReadOnlyInterface {
   sender: string;
   buyShares: ({
     amount,
+    deadline,
+    maxTotalCost,
+    recipient,
+    referrer,
+    settings,
     subject
   }: {
     amount: Uint128;
+    deadline?: Expiration;
+    maxTotalCost?: Uint128;
+    recipient?: string;
+    referrer?: string;
+    settings?: SubjectSettings;
+    subject: string;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  buySharesWithFunds: ({
+    minAmount,
+    subject
+  }: {
+    minAmount: Uint128;
     subject: string;
   }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
   sellShares: ({
     amount,
+    deadline,
+    minProceeds,
+    msg,
+    recipient,
+    referrer,
+    subject
+  }: {
+    amount: Uint128;
+    deadline?: Expiration;
+    minProceeds?: Uint128;
+    msg?: Binary;
+    recipient?: string;
+    referrer?: string;
+    subject: string;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  sellAll: ({
+    minProceeds,
+    subject
+  }: {
+    minProceeds?: Uint128;
+    subject: string;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  batch: ({
+    trades
+  }: {
+    trades: Trade[];
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  transferShares: ({
+    amount,
+    recipient,
+    subject
+  }: {
+    amount: Uint128;
+    recipient: string;
+    subject: string;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  increaseAllowance: ({
+    amount,
+    expires,
+    spender,
+    subject
+  }: {
+    amount: Uint128;
+    expires?: Expiration;
+    spender: string;
+    subject: string;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  decreaseAllowance: ({
+    amount,
+    expires,
+    spender,
+    subject
+  }: {
+    amount: Uint128;
+    expires?: Expiration;
+    spender: string;
+    subject: string;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  transferSharesFrom: ({
+    amount,
+    owner,
+    recipient,
     subject
   }: {
     amount: Uint128;
+    owner: string;
+    recipient: string;
+    subject: string;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  setTransfersEnabled: ({
+    enabled,
+    subject
+  }: {
+    enabled: boolean;
     subject: string;
   }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  updateConfig: ({
+    burnBps,
+    curve,
+    protocolFeeBps,
+    referralFeeBps,
+    subjectBounds,
+    subjectFeeBps
+  }: {
+    burnBps?: number;
+    curve?: CurveConfig;
+    protocolFeeBps?: number;
+    referralFeeBps?: number;
+    subjectBounds?: SubjectBounds;
+    subjectFeeBps?: number;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  updateProtocolFeeRecipients: ({
+    recipients
+  }: {
+    recipients: ProtocolFeeRecipient[];
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  migrateIndexes: ({
+    limit
+  }: {
+    limit?: number;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  proposeNewAdmin: ({
+    expiry,
+    newAdmin
+  }: {
+    expiry?: Expiration;
+    newAdmin: string;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  acceptAdmin: (fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  cancelAdminProposal: (fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  pause: ({
+    allowSells,
+    subject
+  }: {
+    allowSells: boolean;
+    subject?: string;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  unpause: ({
+    subject
+  }: {
+    subject?: string;
+  }, fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  claimFees: (fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  claimReferralFees: (fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
+  withdrawProtocolFees: (fee?: number | StdFee | "auto", memo?: string, _funds?: Coin[]) => Promise<ExecuteResult>;
 }
 export class FrenPartyClient extends FrenPartyQueryClient implements FrenPartyInterface {
   client: SigningCosmWasmClient;
@@ -183,35 +596,326 @@ export class FrenPartyClient extends FrenPartyQueryClient implements FrenPartyIn
     this.sender = sender;
     this.contractAddress = contractAddress;
     this.buyShares = this.buyShares.bind(this);
+    this.buySharesWithFunds = this.buySharesWithFunds.bind(this);
     this.sellShares = this.sellShares.bind(this);
+    this.sellAll = this.sellAll.bind(this);
+    this.batch = this.batch.bind(this);
+    this.transferShares = this.transferShares.bind(this);
+    this.increaseAllowance = this.increaseAllowance.bind(this);
+    this.decreaseAllowance = this.decreaseAllowance.bind(this);
+    this.transferSharesFrom = this.transferSharesFrom.bind(this);
+    this.setTransfersEnabled = this.setTransfersEnabled.bind(this);
+    this.updateConfig = this.updateConfig.bind(this);
+    this.updateProtocolFeeRecipients = this.updateProtocolFeeRecipients.bind(this);
+    this.migrateIndexes = this.migrateIndexes.bind(this);
+    this.proposeNewAdmin = this.proposeNewAdmin.bind(this);
+    this.acceptAdmin = this.acceptAdmin.bind(this);
+    this.cancelAdminProposal = this.cancelAdminProposal.bind(this);
+    this.pause = this.pause.bind(this);
+    this.unpause = this.unpause.bind(this);
+    this.claimFees = this.claimFees.bind(this);
+    this.claimReferralFees = this.claimReferralFees.bind(this);
+    this.withdrawProtocolFees = this.withdrawProtocolFees.bind(this);
   }
 
   buyShares = async ({
     amount,
+    deadline,
+    maxTotalCost,
+    recipient,
+    referrer,
+    settings,
     subject
   }: {
     amount: Uint128;
+    deadline?: Expiration;
+    maxTotalCost?: Uint128;
+    recipient?: string;
+    referrer?: string;
+    settings?: SubjectSettings;
     subject: string;
   }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
     return await this.client.execute(this.sender, this.contractAddress, {
       buy_shares: {
         amount,
+        deadline,
+        max_total_cost: maxTotalCost,
+        recipient,
+        referrer,
+        settings,
+        subject
+      }
+    }, fee, memo, _funds);
+  };
+  buySharesWithFunds = async ({
+    minAmount,
+    subject
+  }: {
+    minAmount: Uint128;
+    subject: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      buy_shares_with_funds: {
+        min_amount: minAmount,
         subject
       }
     }, fee, memo, _funds);
   };
   sellShares = async ({
     amount,
+    deadline,
+    minProceeds,
+    msg,
+    recipient,
+    referrer,
     subject
   }: {
     amount: Uint128;
+    deadline?: Expiration;
+    minProceeds?: Uint128;
+    msg?: Binary;
+    recipient?: string;
+    referrer?: string;
     subject: string;
   }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
     return await this.client.execute(this.sender, this.contractAddress, {
       sell_shares: {
         amount,
+        deadline,
+        min_proceeds: minProceeds,
+        msg,
+        recipient,
+        referrer,
+        subject
+      }
+    }, fee, memo, _funds);
+  };
+  sellAll = async ({
+    minProceeds,
+    subject
+  }: {
+    minProceeds?: Uint128;
+    subject: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      sell_all: {
+        min_proceeds: minProceeds,
+        subject
+      }
+    }, fee, memo, _funds);
+  };
+  batch = async ({
+    trades
+  }: {
+    trades: Trade[];
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      batch: {
+        trades
+      }
+    }, fee, memo, _funds);
+  };
+  transferShares = async ({
+    amount,
+    recipient,
+    subject
+  }: {
+    amount: Uint128;
+    recipient: string;
+    subject: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      transfer_shares: {
+        amount,
+        recipient,
+        subject
+      }
+    }, fee, memo, _funds);
+  };
+  increaseAllowance = async ({
+    amount,
+    expires,
+    spender,
+    subject
+  }: {
+    amount: Uint128;
+    expires?: Expiration;
+    spender: string;
+    subject: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      increase_allowance: {
+        amount,
+        expires,
+        spender,
+        subject
+      }
+    }, fee, memo, _funds);
+  };
+  decreaseAllowance = async ({
+    amount,
+    expires,
+    spender,
+    subject
+  }: {
+    amount: Uint128;
+    expires?: Expiration;
+    spender: string;
+    subject: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      decrease_allowance: {
+        amount,
+        expires,
+        spender,
+        subject
+      }
+    }, fee, memo, _funds);
+  };
+  transferSharesFrom = async ({
+    amount,
+    owner,
+    recipient,
+    subject
+  }: {
+    amount: Uint128;
+    owner: string;
+    recipient: string;
+    subject: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      transfer_shares_from: {
+        amount,
+        owner,
+        recipient,
+        subject
+      }
+    }, fee, memo, _funds);
+  };
+  setTransfersEnabled = async ({
+    enabled,
+    subject
+  }: {
+    enabled: boolean;
+    subject: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      set_transfers_enabled: {
+        enabled,
+        subject
+      }
+    }, fee, memo, _funds);
+  };
+  updateConfig = async ({
+    burnBps,
+    curve,
+    protocolFeeBps,
+    referralFeeBps,
+    subjectBounds,
+    subjectFeeBps
+  }: {
+    burnBps?: number;
+    curve?: CurveConfig;
+    protocolFeeBps?: number;
+    referralFeeBps?: number;
+    subjectBounds?: SubjectBounds;
+    subjectFeeBps?: number;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      update_config: {
+        burn_bps: burnBps,
+        curve,
+        protocol_fee_bps: protocolFeeBps,
+        referral_fee_bps: referralFeeBps,
+        subject_bounds: subjectBounds,
+        subject_fee_bps: subjectFeeBps
+      }
+    }, fee, memo, _funds);
+  };
+  updateProtocolFeeRecipients = async ({
+    recipients
+  }: {
+    recipients: ProtocolFeeRecipient[];
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      update_protocol_fee_recipients: {
+        recipients
+      }
+    }, fee, memo, _funds);
+  };
+  migrateIndexes = async ({
+    limit
+  }: {
+    limit?: number;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      migrate_indexes: {
+        limit
+      }
+    }, fee, memo, _funds);
+  };
+  proposeNewAdmin = async ({
+    expiry,
+    newAdmin
+  }: {
+    expiry?: Expiration;
+    newAdmin: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      propose_new_admin: {
+        expiry,
+        new_admin: newAdmin
+      }
+    }, fee, memo, _funds);
+  };
+  acceptAdmin = async (fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      accept_admin: {}
+    }, fee, memo, _funds);
+  };
+  cancelAdminProposal = async (fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      cancel_admin_proposal: {}
+    }, fee, memo, _funds);
+  };
+  pause = async ({
+    allowSells,
+    subject
+  }: {
+    allowSells: boolean;
+    subject?: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      pause: {
+        allow_sells: allowSells,
+        subject
+      }
+    }, fee, memo, _funds);
+  };
+  unpause = async ({
+    subject
+  }: {
+    subject?: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      unpause: {
         subject
       }
     }, fee, memo, _funds);
   };
+  claimFees = async (fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      claim_fees: {}
+    }, fee, memo, _funds);
+  };
+  claimReferralFees = async (fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      claim_referral_fees: {}
+    }, fee, memo, _funds);
+  };
+  withdrawProtocolFees = async (fee: number | StdFee | "auto" = "auto", memo?: string, _funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      withdraw_protocol_fees: {}
+    }, fee, memo, _funds);
+  };
 }
\ No newline at end of file
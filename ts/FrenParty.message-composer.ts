@@ -7,24 +7,161 @@
 import { MsgExecuteContractEncodeObject } from "@cosmjs/cosmwasm-stargate";
 import { MsgExecuteContract } from "cosmjs-types/cosmwasm/wasm/v1/tx";
 import { toUtf8 } from "@cosmjs/encoding";
-import { Decimal, InstantiateMsg, ExecuteMsg, Uint128, QueryMsg, Coin, Addr, Config } from "./FrenParty.types";
+import { CurveConfig, Decimal, Uint128, InstantiateMsg, Quadratic, Linear, Exponential, Sigmoid, ProtocolFeeRecipient, SubjectBounds, ExecuteMsg, Expiration, Timestamp, Uint64, Binary, Trade, SubjectSettings, QueryMsg, OrderBy, MigrateMsg, AllowanceResponse, Coin, Addr, Config, FeeRecipient, HoldersResponse, Holder, OwnershipResponse, TradingStatus, PauseStatusResponse, PendingFeesResponse, PortfolioResponse, Position, ReferralStatsResponse, TradeSimulation, NullableSubjectConfig, SubjectConfig, SubjectsResponse, SubjectSummary, Boolean } from "./FrenParty.types";
 export interface FrenPartyMsg {
   contractAddress: string;
   sender: string;
   buyShares: ({
     amount,
+    deadline,
+    maxTotalCost,
+    recipient,
+    referrer,
+    settings,
     subject
   }: {
     amount: Uint128;
+    deadline?: Expiration;
+    maxTotalCost?: Uint128;
+    recipient?: string;
+    referrer?: string;
+    settings?: SubjectSettings;
+    subject: string;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  buySharesWithFunds: ({
+    minAmount,
+    subject
+  }: {
+    minAmount: Uint128;
     subject: string;
   }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
   sellShares: ({
     amount,
+    deadline,
+    minProceeds,
+    msg,
+    recipient,
+    referrer,
+    subject
+  }: {
+    amount: Uint128;
+    deadline?: Expiration;
+    minProceeds?: Uint128;
+    msg?: Binary;
+    recipient?: string;
+    referrer?: string;
+    subject: string;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  sellAll: ({
+    minProceeds,
+    subject
+  }: {
+    minProceeds?: Uint128;
+    subject: string;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  batch: ({
+    trades
+  }: {
+    trades: Trade[];
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  transferShares: ({
+    amount,
+    recipient,
+    subject
+  }: {
+    amount: Uint128;
+    recipient: string;
+    subject: string;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  increaseAllowance: ({
+    amount,
+    expires,
+    spender,
     subject
   }: {
     amount: Uint128;
+    expires?: Expiration;
+    spender: string;
     subject: string;
   }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  decreaseAllowance: ({
+    amount,
+    expires,
+    spender,
+    subject
+  }: {
+    amount: Uint128;
+    expires?: Expiration;
+    spender: string;
+    subject: string;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  transferSharesFrom: ({
+    amount,
+    owner,
+    recipient,
+    subject
+  }: {
+    amount: Uint128;
+    owner: string;
+    recipient: string;
+    subject: string;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  setTransfersEnabled: ({
+    enabled,
+    subject
+  }: {
+    enabled: boolean;
+    subject: string;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  updateConfig: ({
+    burnBps,
+    curve,
+    protocolFeeBps,
+    referralFeeBps,
+    subjectBounds,
+    subjectFeeBps
+  }: {
+    burnBps?: number;
+    curve?: CurveConfig;
+    protocolFeeBps?: number;
+    referralFeeBps?: number;
+    subjectBounds?: SubjectBounds;
+    subjectFeeBps?: number;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  updateProtocolFeeRecipients: ({
+    recipients
+  }: {
+    recipients: ProtocolFeeRecipient[];
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  migrateIndexes: ({
+    limit
+  }: {
+    limit?: number;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  proposeNewAdmin: ({
+    expiry,
+    newAdmin
+  }: {
+    expiry?: Expiration;
+    newAdmin: string;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  acceptAdmin: (_funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  cancelAdminProposal: (_funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  pause: ({
+    allowSells,
+    subject
+  }: {
+    allowSells: boolean;
+    subject?: string;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  unpause: ({
+    subject
+  }: {
+    subject?: string;
+  }, _funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  claimFees: (_funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  claimReferralFees: (_funds?: Coin[]) => MsgExecuteContractEncodeObject;
+  withdrawProtocolFees: (_funds?: Coin[]) => MsgExecuteContractEncodeObject;
 }
 export class FrenPartyMsgComposer implements FrenPartyMsg {
   sender: string;
@@ -34,14 +171,43 @@ export class FrenPartyMsgComposer implements FrenPartyMsg {
     this.sender = sender;
     this.contractAddress = contractAddress;
     this.buyShares = this.buyShares.bind(this);
+    this.buySharesWithFunds = this.buySharesWithFunds.bind(this);
     this.sellShares = this.sellShares.bind(this);
+    this.sellAll = this.sellAll.bind(this);
+    this.batch = this.batch.bind(this);
+    this.transferShares = this.transferShares.bind(this);
+    this.increaseAllowance = this.increaseAllowance.bind(this);
+    this.decreaseAllowance = this.decreaseAllowance.bind(this);
+    this.transferSharesFrom = this.transferSharesFrom.bind(this);
+    this.setTransfersEnabled = this.setTransfersEnabled.bind(this);
+    this.updateConfig = this.updateConfig.bind(this);
+    this.updateProtocolFeeRecipients = this.updateProtocolFeeRecipients.bind(this);
+    this.migrateIndexes = this.migrateIndexes.bind(this);
+    this.proposeNewAdmin = this.proposeNewAdmin.bind(this);
+    this.acceptAdmin = this.acceptAdmin.bind(this);
+    this.cancelAdminProposal = this.cancelAdminProposal.bind(this);
+    this.pause = this.pause.bind(this);
+    this.unpause = this.unpause.bind(this);
+    this.claimFees = this.claimFees.bind(this);
+    this.claimReferralFees = this.claimReferralFees.bind(this);
+    this.withdrawProtocolFees = this.withdrawProtocolFees.bind(this);
   }
 
   buyShares = ({
     amount,
+    deadline,
+    maxTotalCost,
+    recipient,
+    referrer,
+    settings,
     subject
   }: {
     amount: Uint128;
+    deadline?: Expiration;
+    maxTotalCost?: Uint128;
+    recipient?: string;
+    referrer?: string;
+    settings?: SubjectSettings;
     subject: string;
   }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
     return {
@@ -52,6 +218,33 @@ export class FrenPartyMsgComposer implements FrenPartyMsg {
         msg: toUtf8(JSON.stringify({
           buy_shares: {
             amount,
+            deadline,
+            max_total_cost: maxTotalCost,
+            recipient,
+            referrer,
+            settings,
+            subject
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  buySharesWithFunds = ({
+    minAmount,
+    subject
+  }: {
+    minAmount: Uint128;
+    subject: string;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          buy_shares_with_funds: {
+            min_amount: minAmount,
             subject
           }
         })),
@@ -61,9 +254,19 @@ export class FrenPartyMsgComposer implements FrenPartyMsg {
   };
   sellShares = ({
     amount,
+    deadline,
+    minProceeds,
+    msg,
+    recipient,
+    referrer,
     subject
   }: {
     amount: Uint128;
+    deadline?: Expiration;
+    minProceeds?: Uint128;
+    msg?: Binary;
+    recipient?: string;
+    referrer?: string;
     subject: string;
   }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
     return {
@@ -74,6 +277,325 @@ export class FrenPartyMsgComposer implements FrenPartyMsg {
         msg: toUtf8(JSON.stringify({
           sell_shares: {
             amount,
+            deadline,
+            min_proceeds: minProceeds,
+            msg,
+            recipient,
+            referrer,
+            subject
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  sellAll = ({
+    minProceeds,
+    subject
+  }: {
+    minProceeds?: Uint128;
+    subject: string;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          sell_all: {
+            min_proceeds: minProceeds,
+            subject
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  batch = ({
+    trades
+  }: {
+    trades: Trade[];
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          batch: {
+            trades
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  transferShares = ({
+    amount,
+    recipient,
+    subject
+  }: {
+    amount: Uint128;
+    recipient: string;
+    subject: string;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          transfer_shares: {
+            amount,
+            recipient,
+            subject
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  increaseAllowance = ({
+    amount,
+    expires,
+    spender,
+    subject
+  }: {
+    amount: Uint128;
+    expires?: Expiration;
+    spender: string;
+    subject: string;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          increase_allowance: {
+            amount,
+            expires,
+            spender,
+            subject
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  decreaseAllowance = ({
+    amount,
+    expires,
+    spender,
+    subject
+  }: {
+    amount: Uint128;
+    expires?: Expiration;
+    spender: string;
+    subject: string;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          decrease_allowance: {
+            amount,
+            expires,
+            spender,
+            subject
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  transferSharesFrom = ({
+    amount,
+    owner,
+    recipient,
+    subject
+  }: {
+    amount: Uint128;
+    owner: string;
+    recipient: string;
+    subject: string;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          transfer_shares_from: {
+            amount,
+            owner,
+            recipient,
+            subject
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  setTransfersEnabled = ({
+    enabled,
+    subject
+  }: {
+    enabled: boolean;
+    subject: string;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          set_transfers_enabled: {
+            enabled,
+            subject
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  updateConfig = ({
+    burnBps,
+    curve,
+    protocolFeeBps,
+    referralFeeBps,
+    subjectBounds,
+    subjectFeeBps
+  }: {
+    burnBps?: number;
+    curve?: CurveConfig;
+    protocolFeeBps?: number;
+    referralFeeBps?: number;
+    subjectBounds?: SubjectBounds;
+    subjectFeeBps?: number;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          update_config: {
+            burn_bps: burnBps,
+            curve,
+            protocol_fee_bps: protocolFeeBps,
+            referral_fee_bps: referralFeeBps,
+            subject_bounds: subjectBounds,
+            subject_fee_bps: subjectFeeBps
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  updateProtocolFeeRecipients = ({
+    recipients
+  }: {
+    recipients: ProtocolFeeRecipient[];
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          update_protocol_fee_recipients: {
+            recipients
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  migrateIndexes = ({
+    limit
+  }: {
+    limit?: number;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          migrate_indexes: {
+            limit
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  proposeNewAdmin = ({
+    expiry,
+    newAdmin
+  }: {
+    expiry?: Expiration;
+    newAdmin: string;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          propose_new_admin: {
+            expiry,
+            new_admin: newAdmin
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  acceptAdmin = (_funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          accept_admin: {}
+        })),
+        funds: _funds
+      })
+    };
+  };
+  cancelAdminProposal = (_funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          cancel_admin_proposal: {}
+        })),
+        funds: _funds
+      })
+    };
+  };
+  pause = ({
+    allowSells,
+    subject
+  }: {
+    allowSells: boolean;
+    subject?: string;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          pause: {
+            allow_sells: allowSells,
             subject
           }
         })),
@@ -81,4 +603,62 @@ export class FrenPartyMsgComposer implements FrenPartyMsg {
       })
     };
   };
+  unpause = ({
+    subject
+  }: {
+    subject?: string;
+  }, _funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          unpause: {
+            subject
+          }
+        })),
+        funds: _funds
+      })
+    };
+  };
+  claimFees = (_funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          claim_fees: {}
+        })),
+        funds: _funds
+      })
+    };
+  };
+  claimReferralFees = (_funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          claim_referral_fees: {}
+        })),
+        funds: _funds
+      })
+    };
+  };
+  withdrawProtocolFees = (_funds?: Coin[]): MsgExecuteContractEncodeObject => {
+    return {
+      typeUrl: "/cosmwasm.wasm.v1.MsgExecuteContract",
+      value: MsgExecuteContract.fromPartial({
+        sender: this.sender,
+        contract: this.contractAddress,
+        msg: toUtf8(JSON.stringify({
+          withdraw_protocol_fees: {}
+        })),
+        funds: _funds
+      })
+    };
+  };
 }
\ No newline at end of file
@@ -4,27 +4,216 @@
 * and run the @cosmwasm/ts-codegen generate command to regenerate this file.
 */
 
+export type CurveConfig = {
+  quadratic: Quadratic;
+} | {
+  linear: Linear;
+} | {
+  exponential: Exponential;
+} | {
+  sigmoid: Sigmoid;
+};
 export type Decimal = string;
+export type Uint128 = string;
 export interface InstantiateMsg {
-  curve_coefficient: Decimal;
+  burn_bps: number;
+  curve: CurveConfig;
+  denom?: string | null;
   protocol_fee_bps: number;
-  protocol_fee_destination: string;
+  protocol_fee_recipients: ProtocolFeeRecipient[];
+  referral_fee_bps: number;
+  subject_bounds?: SubjectBounds | null;
   subject_fee_bps: number;
 }
+export interface Quadratic {
+  coefficient: Decimal;
+}
+export interface Linear {
+  coefficient: Decimal;
+}
+export interface Exponential {
+  coefficient: Decimal;
+  growth_rate: Decimal;
+}
+export interface Sigmoid {
+  coefficient: Decimal;
+  midpoint: Uint128;
+  smoothing: Decimal;
+}
+export interface ProtocolFeeRecipient {
+  address: string;
+  weight_bps: number;
+}
+export interface SubjectBounds {
+  max_curve_coefficient: Decimal;
+  max_subject_fee_bps: number;
+  min_curve_coefficient: Decimal;
+}
 export type ExecuteMsg = {
   buy_shares: {
     amount: Uint128;
+    deadline?: Expiration | null;
+    max_total_cost?: Uint128 | null;
+    recipient?: string | null;
+    referrer?: string | null;
+    settings?: SubjectSettings | null;
+    subject: string;
+  };
+} | {
+  buy_shares_with_funds: {
+    min_amount: Uint128;
     subject: string;
   };
 } | {
   sell_shares: {
     amount: Uint128;
+    deadline?: Expiration | null;
+    min_proceeds?: Uint128 | null;
+    msg?: Binary | null;
+    recipient?: string | null;
+    referrer?: string | null;
+    subject: string;
+  };
+} | {
+  sell_all: {
+    min_proceeds?: Uint128 | null;
+    subject: string;
+  };
+} | {
+  batch: {
+    trades: Trade[];
+  };
+} | {
+  transfer_shares: {
+    amount: Uint128;
+    recipient: string;
+    subject: string;
+  };
+} | {
+  increase_allowance: {
+    amount: Uint128;
+    expires?: Expiration | null;
+    spender: string;
+    subject: string;
+  };
+} | {
+  decrease_allowance: {
+    amount: Uint128;
+    expires?: Expiration | null;
+    spender: string;
+    subject: string;
+  };
+} | {
+  transfer_shares_from: {
+    amount: Uint128;
+    owner: string;
+    recipient: string;
+    subject: string;
+  };
+} | {
+  set_transfers_enabled: {
+    enabled: boolean;
     subject: string;
   };
+} | {
+  update_config: {
+    burn_bps?: number | null;
+    curve?: CurveConfig | null;
+    protocol_fee_bps?: number | null;
+    referral_fee_bps?: number | null;
+    subject_bounds?: SubjectBounds | null;
+    subject_fee_bps?: number | null;
+  };
+} | {
+  update_protocol_fee_recipients: {
+    recipients: ProtocolFeeRecipient[];
+  };
+} | {
+  migrate_indexes: {
+    limit?: number | null;
+  };
+} | {
+  propose_new_admin: {
+    expiry?: Expiration | null;
+    new_admin: string;
+  };
+} | {
+  accept_admin: {};
+} | {
+  cancel_admin_proposal: {};
+} | {
+  pause: {
+    allow_sells: boolean;
+    subject?: string | null;
+  };
+} | {
+  unpause: {
+    subject?: string | null;
+  };
+} | {
+  claim_fees: {};
+} | {
+  claim_referral_fees: {};
+} | {
+  withdraw_protocol_fees: {};
 };
-export type Uint128 = string;
+export type Expiration = {
+  at_height: number;
+} | {
+  at_time: Timestamp;
+} | {
+  never: {};
+};
+export type Timestamp = Uint64;
+export type Uint64 = string;
+export type Binary = string;
+export type Trade = {
+  buy: {
+    amount: Uint128;
+    subject: string;
+  };
+} | {
+  sell: {
+    amount: Uint128;
+    subject: string;
+  };
+};
+export interface SubjectSettings {
+  curve_coefficient: Decimal;
+  subject_fee_bps: number;
+}
 export type QueryMsg = {
   config: {};
+} | {
+  ownership: {};
+} | {
+  pause_status: {
+    subject?: string | null;
+  };
+} | {
+  portfolio: {
+    holder: string;
+    limit?: number | null;
+    start_after?: string | null;
+  };
+} | {
+  subjects: {
+    limit?: number | null;
+    order_by?: OrderBy | null;
+    start_after?: string | null;
+  };
+} | {
+  pending_fees: {
+    address: string;
+  };
+} | {
+  referral_stats: {
+    referrer: string;
+  };
+} | {
+  subject_config: {
+    subject: string;
+  };
 } | {
   shares_balance: {
     holder: string;
@@ -34,6 +223,39 @@ export type QueryMsg = {
   shares_supply: {
     subject: string;
   };
+} | {
+  allowance: {
+    owner: string;
+    spender: string;
+    subject: string;
+  };
+} | {
+  transfers_enabled: {
+    subject: string;
+  };
+} | {
+  holders: {
+    limit?: number | null;
+    start_after?: string | null;
+    subject: string;
+  };
+} | {
+  simulate_buy: {
+    amount: Uint128;
+    subject: string;
+    trader: string;
+  };
+} | {
+  simulate_sell: {
+    amount: Uint128;
+    subject: string;
+    trader: string;
+  };
+} | {
+  max_buy_for_funds: {
+    funds: Uint128;
+    subject: string;
+  };
 } | {
   buy_price: {
     amount: Uint128;
@@ -55,6 +277,14 @@ export type QueryMsg = {
     subject: string;
   };
 };
+export type OrderBy = "ascending" | "descending";
+export interface MigrateMsg {
+  admin?: string | null;
+}
+export interface AllowanceResponse {
+  allowance: Uint128;
+  expires: Expiration;
+}
 export interface Coin {
   amount: Uint128;
   denom: string;
@@ -62,8 +292,74 @@ export interface Coin {
 }
 export type Addr = string;
 export interface Config {
-  curve_coefficient: Decimal;
-  protocol_fee_destination: Addr;
+  burn_percent: Decimal;
+  curve: CurveConfig;
+  denom: string;
   protocol_fee_percent: Decimal;
+  protocol_fee_recipients: FeeRecipient[];
+  referral_fee_percent: Decimal;
+  subject_bounds?: SubjectBounds | null;
   subject_fee_percent: Decimal;
-}
\ No newline at end of file
+}
+export interface FeeRecipient {
+  address: Addr;
+  weight: Decimal;
+}
+export interface HoldersResponse {
+  holders: Holder[];
+}
+export interface Holder {
+  address: Addr;
+  balance: Uint128;
+}
+export interface OwnershipResponse {
+  admin: Addr;
+  pending_admin?: Addr | null;
+  pending_expiry?: Expiration | null;
+}
+export type TradingStatus = ("active" | "paused") | "sells_only";
+export interface PauseStatusResponse {
+  global: TradingStatus;
+  subject?: TradingStatus | null;
+}
+export interface PendingFeesResponse {
+  protocol_fees: Uint128;
+  subject_fees: Uint128;
+}
+export interface PortfolioResponse {
+  positions: Position[];
+}
+export interface Position {
+  balance: Uint128;
+  sell_value: Uint128;
+  subject: Addr;
+}
+export interface ReferralStatsResponse {
+  earned: Uint128;
+  pending: Uint128;
+  trade_count: number;
+  volume: Uint128;
+}
+export interface TradeSimulation {
+  balance: Uint128;
+  price: Uint128;
+  protocol_fee: Uint128;
+  subject_fee: Uint128;
+  supply: Uint128;
+  total: Uint128;
+}
+export type NullableSubjectConfig = SubjectConfig | null;
+export interface SubjectConfig {
+  curve: CurveConfig;
+  subject_fee_percent?: Decimal | null;
+}
+export interface SubjectsResponse {
+  subjects: SubjectSummary[];
+}
+export interface SubjectSummary {
+  created_at: number;
+  holder_count: number;
+  subject: Addr;
+  supply: Uint128;
+}
+export type Boolean = boolean;
\ No newline at end of file